@@ -0,0 +1,209 @@
+//! # Errors
+//!
+//! Every method of [`Notion`](crate::Notion) returns a [`NotionError`] on failure, so callers can
+//! `match` on the kind of failure instead of inspecting error messages.
+//!
+//! ```rust
+//! # use notion_tools::error::{ErrorCode, NotionError};
+//! fn should_retry_later(error: &NotionError) -> bool {
+//!     match error {
+//!         NotionError::Api { code: ErrorCode::RateLimited, .. } => true,
+//!         NotionError::Api { status, .. } => *status >= 500,
+//!         NotionError::Http(e) => e.is_timeout(),
+//!         _ => false,
+//!     }
+//! }
+//! ```
+//!
+use serde::Deserialize;
+use std::fmt;
+use std::time::Duration;
+
+/// Error codes returned by the Notion API
+/// See <https://developers.notion.com/reference/status-codes>
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    InvalidJson,
+    InvalidRequestUrl,
+    InvalidRequest,
+    InvalidGrant,
+    ValidationError,
+    MissingVersion,
+    Unauthorized,
+    RestrictedResource,
+    ObjectNotFound,
+    ConflictError,
+    RateLimited,
+    InternalServerError,
+    BadGateway,
+    ServiceUnavailable,
+    DatabaseConnectionUnavailable,
+    GatewayTimeout,
+    /// A code unknown to this library, or an error body that could not be parsed
+    Other(String),
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ErrorCode::InvalidJson => "invalid_json",
+            ErrorCode::InvalidRequestUrl => "invalid_request_url",
+            ErrorCode::InvalidRequest => "invalid_request",
+            ErrorCode::InvalidGrant => "invalid_grant",
+            ErrorCode::ValidationError => "validation_error",
+            ErrorCode::MissingVersion => "missing_version",
+            ErrorCode::Unauthorized => "unauthorized",
+            ErrorCode::RestrictedResource => "restricted_resource",
+            ErrorCode::ObjectNotFound => "object_not_found",
+            ErrorCode::ConflictError => "conflict_error",
+            ErrorCode::RateLimited => "rate_limited",
+            ErrorCode::InternalServerError => "internal_server_error",
+            ErrorCode::BadGateway => "bad_gateway",
+            ErrorCode::ServiceUnavailable => "service_unavailable",
+            ErrorCode::DatabaseConnectionUnavailable => "database_connection_unavailable",
+            ErrorCode::GatewayTimeout => "gateway_timeout",
+            ErrorCode::Other(code) => code,
+        }
+    }
+
+    /// Error code implied by an HTTP status, for error responses without a JSON body
+    fn from_status(status: u16) -> Self {
+        match status {
+            429 => ErrorCode::RateLimited,
+            500 => ErrorCode::InternalServerError,
+            502 => ErrorCode::BadGateway,
+            503 => ErrorCode::ServiceUnavailable,
+            504 => ErrorCode::GatewayTimeout,
+            _ => ErrorCode::Other(String::new()),
+        }
+    }
+}
+
+impl From<&str> for ErrorCode {
+    fn from(code: &str) -> Self {
+        match code {
+            "invalid_json" => ErrorCode::InvalidJson,
+            "invalid_request_url" => ErrorCode::InvalidRequestUrl,
+            "invalid_request" => ErrorCode::InvalidRequest,
+            "invalid_grant" => ErrorCode::InvalidGrant,
+            "validation_error" => ErrorCode::ValidationError,
+            "missing_version" => ErrorCode::MissingVersion,
+            "unauthorized" => ErrorCode::Unauthorized,
+            "restricted_resource" => ErrorCode::RestrictedResource,
+            "object_not_found" => ErrorCode::ObjectNotFound,
+            "conflict_error" => ErrorCode::ConflictError,
+            "rate_limited" => ErrorCode::RateLimited,
+            "internal_server_error" => ErrorCode::InternalServerError,
+            "bad_gateway" => ErrorCode::BadGateway,
+            "service_unavailable" => ErrorCode::ServiceUnavailable,
+            "database_connection_unavailable" => ErrorCode::DatabaseConnectionUnavailable,
+            "gateway_timeout" => ErrorCode::GatewayTimeout,
+            other => ErrorCode::Other(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Errors returned by the Notion API client
+#[derive(Debug, thiserror::Error)]
+pub enum NotionError {
+    /// The Notion API answered with a non-success HTTP status
+    #[error("Notion API error {status} ({code}): {message}")]
+    Api {
+        /// HTTP status code
+        status: u16,
+        /// Error code from the response body
+        code: ErrorCode,
+        /// Human readable message from the response body
+        message: String,
+        /// Request ID to quote when contacting Notion support
+        request_id: Option<String>,
+        /// Delay requested by the `Retry-After` header
+        retry_after: Option<Duration>,
+    },
+    /// The request could not be sent or the response could not be read
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    /// A request or response body could not be (de)serialized
+    #[error("Failed to (de)serialize JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    /// A required environment variable is not set
+    #[error("Environment variable {0} is not set")]
+    MissingEnvVar(String),
+    /// A date is not a valid ISO 8601 date or date-time
+    #[error("Invalid ISO 8601 date: {0}")]
+    InvalidDate(String),
+    /// A request URL could not be built, e.g. because the base URL is malformed
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(String),
+}
+
+impl NotionError {
+    /// Build an [`NotionError::Api`] from an error response
+    pub(crate) fn from_response(status: u16, body: &str, retry_after: Option<Duration>) -> Self {
+        #[derive(Deserialize)]
+        struct ErrorBody {
+            #[serde(default = "String::new")]
+            code: String,
+            #[serde(default = "String::new")]
+            message: String,
+            #[serde(default = "Option::default")]
+            request_id: Option<String>,
+        }
+
+        match serde_json::from_str::<ErrorBody>(body) {
+            Ok(error) => NotionError::Api {
+                status,
+                // gateways answer with JSON bodies that have no `code`
+                code: if error.code.is_empty() {
+                    ErrorCode::from_status(status)
+                } else {
+                    ErrorCode::from(error.code.as_str())
+                },
+                message: error.message,
+                request_id: error.request_id,
+                retry_after,
+            },
+            Err(_) => NotionError::Api {
+                status,
+                code: ErrorCode::from_status(status),
+                message: body.to_string(),
+                request_id: None,
+                retry_after,
+            },
+        }
+    }
+
+    /// Error code returned by the Notion API, if any
+    pub fn code(&self) -> Option<&ErrorCode> {
+        match self {
+            NotionError::Api { code, .. } => Some(code),
+            _ => None,
+        }
+    }
+
+    /// HTTP status code, if a response was received
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            NotionError::Api { status, .. } => Some(*status),
+            NotionError::Http(e) => e.status().map(|s| s.as_u16()),
+            _ => None,
+        }
+    }
+
+    /// Request ID returned by the Notion API, if any
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            NotionError::Api { request_id, .. } => request_id.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Result type returned by the Notion API client
+pub type Result<T> = std::result::Result<T, NotionError>;
@@ -6,26 +6,29 @@
 //!
 //! ## Usage
 //!
-//! To use this library, you need to set the `NOTION_API_KEY` and `NOTION_DATABASE_ID` environment
-//! variables. The `NOTION_API_KEY` is required for authentication, while the `NOTION_DATABASE_ID`
-//! is optional and can be set later using the `database` method.
+//! Create a client with [`Notion::from_env`], which reads the `NOTION_API_KEY` environment variable
+//! (or a `.env` file). The `NOTION_API_KEY` is required for authentication.
+//! If you load your API key from somewhere else, use [`Notion::with_token`] or [`Notion::builder`].
+//!
+//! The client holds no per-database state: database IDs are passed to each call, so a single client
+//! (or an `Arc<Notion>`) can serve many databases concurrently.
 //!
 //! ## Implemented endpoints
 //! | Endpoint | Implemented | Code |
 //! |---|:---:|---|
 //! | [Create a Token](https://developers.notion.com/reference/create-a-token) | - | |
-//! | [Append block children](https://developers.notion.com/reference/patch-block-children) | ✅ | [`Notion::append_block_children`](Notion) |
-//! | [Retrieve a block](https://developers.notion.com/reference/retrieve-a-block) | - | |
-//! | [Retrieve block children](https://developers.notion.com/reference/get-block-children) | - | |
-//! | [Update a block](https://developers.notion.com/reference/update-a-block) | - | |
-//! | [Delete a block](https://developers.notion.com/reference/delete-a-block) | - | |
+//! | [Append block children](https://developers.notion.com/reference/patch-block-children) | ✅ | [`Notion::append_block_children`](Notion), [`Notion::append_block_children_after`](Notion), [`Notion::append_block_children_deep`](Notion) |
+//! | [Retrieve a block](https://developers.notion.com/reference/retrieve-a-block) | ✅ | [`Notion::retrieve_a_block`](Notion) |
+//! | [Retrieve block children](https://developers.notion.com/reference/get-block-children) | ✅ | [`Notion::retrieve_block_children`](Notion), [`Notion::retrieve_block_children_stream`](Notion), [`Notion::retrieve_block_children_all`](Notion), [`Notion::retrieve_page_content`](Notion) |
+//! | [Update a block](https://developers.notion.com/reference/update-a-block) | ✅ | [`Notion::update_a_block`](Notion) |
+//! | [Delete a block](https://developers.notion.com/reference/delete-a-block) | ✅ | [`Notion::delete_a_block`](Notion), [`Notion::restore_a_block`](Notion) |
 //! | [Create a page](https://developers.notion.com/reference/post-page) | ✅ | [`Notion::create_a_page`](Notion) |
 //! | [Retrieve a page](https://developers.notion.com/reference/retrieve-a-page) | ✅ | [`Notion::retrieve_a_page`](Notion) |
 //! | [Retrieve a page property item](https://developers.notion.com/reference/retrieve-a-page-property-item) | - | |
 //! | [Update page properties](https://developers.notion.com/reference/patch-page) | ✅ | [`Notion::update_a_page`](Notion) |
 //! | [Archive a page](https://developers.notion.com/reference/archive-a-page) | ✅ | [`Notion::archive_a_page`](Notion) |
 //! | [Create a database](https://developers.notion.com/reference/create-a-database) | - | |
-//! | [Query a database](https://developers.notion.com/reference/post-database-query) | ✅ | [`Notion::query_database`](Notion) |
+//! | [Query a database](https://developers.notion.com/reference/post-database-query) | ✅ | [`Notion::query_database`](Notion), [`Notion::query_database_stream`](Notion), [`Notion::query_database_all`](Notion) |
 //! | [Retrieve a database](https://developers.notion.com/reference/retrieve-a-database) | ✅ | [`Notion::retrieve_a_database`](Notion) |
 //! | [Update a database](https://developers.notion.com/reference/update-a-database) | - | |
 //! | [List all users](https://developers.notion.com/reference/get-users) | - | |
@@ -35,16 +38,26 @@
 //! | [Retrieve comments](https://developers.notion.com/reference/retrieve-a-comment) | - | |
 //! | [Search by title](https://developers.notion.com/reference/post-search) | - | |
 //!
+//! ## Features
+//! - `chrono`: pass `chrono` dates and date-times to date filters and date properties, read page
+//!   timestamps as date-times, and reject malformed ISO 8601 dates before a request is sent.
+//!
+//! ## Errors
+//! All methods return [`error::NotionError`] on failure. API errors carry the HTTP status, the Notion
+//! [`error::ErrorCode`] and the request ID, so you can `match` on the kind of failure.
+//!
 //! ## Build a query filter
 //! The `QueryFilter` struct is used to build a query filter for querying a database. The `QueryFilter`
 //! struct provides methods for building a filter that can be used to query a database.
 //! See the [`QueryFilter`] struct for more information.
+//! [`structs::filter_dsl::prop`] builds the same filters with `&` and `|`, e.g.
+//! `prop("Status").status().eq("Active") & prop("Age").number().gt(18)`.
 //!
 //! ## Examples
 //!
 //! ### Create a page
 //!
-//! ```rust
+//! ```rust,no_run
 //! # use anyhow::Result;
 //! # use notion_tools::Notion;
 //! # use notion_tools::structs::page::*;
@@ -53,7 +66,7 @@
 //!
 //! # #[tokio::main]
 //! # async fn main() -> Result<()> {
-//! let notion = Notion::new();
+//! let notion = Notion::from_env()?;
 //!
 //! // Create a page
 //! let mut properties: FxHashMap<String, PageProperty> = FxHashMap::default();
@@ -68,7 +81,7 @@
 //! properties.insert(String::from("Status"), PageProperty::status(String::from("ToDo")));
 //! let mut page = Page::from_properties(properties);
 //! page.parent.type_name = ParentType::Database;
-//! page.parent.database_id = Some(notion.database_id.clone());
+//! page.parent.database_id = Some(String::from("your_database_id"));
 //!
 //! let response = notion.create_a_page(&page).await;
 //! println!("{:?}", response);
@@ -78,7 +91,7 @@
 //!
 //! ### Query a database
 //!
-//! ```rust
+//! ```rust,no_run
 //! # use anyhow::Result;
 //! # use notion_tools::Notion;
 //! # use notion_tools::structs::query_filter::*;
@@ -87,9 +100,8 @@
 //! #
 //! # #[tokio::main]
 //! # async fn main() -> Result<()> {
-//! let mut notion = Notion::new();
-//! notion.database(String::from("your_database_id"));
-//! # notion.database(std::env::var("NOTION_DATABASE_ID").unwrap());
+//! let notion = Notion::from_env()?;
+//! let database_id = String::from("your_database_id");
 //!
 //! // Build a query filter
 //! let mut filter = QueryFilter::new();
@@ -98,131 +110,354 @@
 //!     StatusFilterItem::equals(String::from("ToDo")),
 //! ));
 //! // Query a database
-//! let response = notion.query_database(filter).await?;
+//! let response = notion.query_database(database_id, filter).await?;
 //! println!("{:?}", response);
 //! #     Ok(())
 //! # }
 //! ```
 //!
+pub mod error;
+pub mod rate_limit;
+pub mod retry;
 pub mod structs;
 
+use crate::error::{NotionError, Result};
+use crate::rate_limit::RateLimiter;
+use crate::retry::RetryPolicy;
 use crate::structs::block::*;
 use crate::structs::common::*;
 use crate::structs::database::*;
 use crate::structs::page::*;
 use crate::structs::query_filter::*;
-use anyhow::{Error, Result};
 use dotenvy::dotenv;
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use fxhash::FxHashMap;
 use reqwest as request;
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+
+/// Root URL of the Notion API
+pub const NOTION_API_BASE_URL: &str = "https://api.notion.com/v1";
 
-/// Notion API client
-#[derive(Debug)]
+/// Maximum number of requests sent at once by methods that fetch many blocks
+const MAX_CONCURRENT_REQUESTS: usize = 4;
+
+/// Maximum number of blocks, at any depth, in one "Append block children" request
+const MAX_BLOCKS_PER_REQUEST: usize = 1000;
+/// Maximum levels of children under a block in one "Append block children" request
+const MAX_NESTING_PER_REQUEST: usize = 2;
+
+/// Notion API client  
+/// Cloning is cheap: clones share the same connection pool.
+#[derive(Debug, Clone)]
 pub struct Notion {
     /// Notion API key: set from the `NOTION_API_KEY` environment variable
     pub api_key: String,
-    /// Notion database ID: set from the `NOTION_DATABASE_ID` environment variable
-    pub database_id: String,
+    /// Root URL every endpoint is built from
+    base_url: String,
+    /// HTTP client reused by every request
+    client: request::Client,
+    /// Retry policy applied to every request
+    retry_policy: RetryPolicy,
+    /// Client-side throttle shared by every clone
+    rate_limiter: Option<RateLimiter>,
+}
+
+/// Builder for [`Notion`]
+///
+/// ```rust
+/// # use notion_tools::Notion;
+/// let notion = Notion::builder()
+///     .api_key(String::from("secret_xxx"))
+///     .base_url(String::from("http://127.0.0.1:8080/v1"))
+///     .build();
+/// assert_eq!(notion.base_url(), "http://127.0.0.1:8080/v1");
+/// ```
+#[derive(Debug, Default)]
+pub struct NotionBuilder {
+    api_key: Option<String>,
+    base_url: Option<String>,
+    client: Option<request::Client>,
+    retry_policy: Option<RetryPolicy>,
+    rate_limiter: Option<RateLimiter>,
+}
+
+impl NotionBuilder {
+    /// Set the Notion API key
+    pub fn api_key(mut self, api_key: String) -> Self {
+        self.api_key = Some(api_key);
+        self
+    }
+
+    /// Set the root URL of the API, e.g. a local mock server.  
+    /// Defaults to [`NOTION_API_BASE_URL`].
+    pub fn base_url(mut self, base_url: String) -> Self {
+        self.base_url = Some(base_url);
+        self
+    }
+
+    /// Use your own [`reqwest::Client`], e.g. to configure timeouts, proxies or the user agent.  
+    /// Defaults to `reqwest::Client::new()`.
+    pub fn client(mut self, client: request::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Set the [`RetryPolicy`] applied to every request.  
+    /// Defaults to `RetryPolicy::default()`; use [`RetryPolicy::none`] to disable retries.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Throttle requests with a [`RateLimiter`].  
+    /// Clones of the client, and other clients built with a clone of the same limiter, share its budget.
+    /// No client-side throttling is applied by default.
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Build the [`Notion`] client
+    pub fn build(self) -> Notion {
+        let base_url = self
+            .base_url
+            .unwrap_or_else(|| NOTION_API_BASE_URL.to_string());
+        Notion {
+            api_key: self.api_key.unwrap_or_default(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client: self.client.unwrap_or_default(),
+            retry_policy: self.retry_policy.unwrap_or_default(),
+            rate_limiter: self.rate_limiter,
+        }
+    }
 }
 
 impl Notion {
     /// Create a new Notion API client.  
     /// environment variables are read from the `.env` file.
+    /// ## Panics
+    /// - if `NOTION_API_KEY` is not set
+    #[deprecated(
+        since = "0.1.8",
+        note = "use `Notion::from_env` or `Notion::with_token` instead"
+    )]
     pub fn new() -> Self {
+        Notion::from_env().expect("NOTION_API_KEY must be set")
+    }
+
+    /// Create a new Notion API client from the environment.  
+    /// environment variables are read from the `.env` file.
+    /// ## Return
+    /// - [`NotionError::MissingEnvVar`] if `NOTION_API_KEY` is not set
+    pub fn from_env() -> Result<Self> {
         dotenv().ok();
-        let api_key = std::env::var("NOTION_API_KEY").expect("NOTION_API_KEY must be set");
-        let database_id = std::env::var("NOTION_DATABASE_ID").unwrap_or("".to_string());
+        let api_key = std::env::var("NOTION_API_KEY")
+            .map_err(|_| NotionError::MissingEnvVar(String::from("NOTION_API_KEY")))?;
+        Ok(Notion::builder().api_key(api_key).build())
+    }
 
-        Notion {
-            api_key,
-            database_id,
-        }
+    /// Create a new Notion API client with the given API key,
+    /// e.g. one loaded from a vault or a config file.
+    pub fn with_token(api_key: String) -> Self {
+        Notion::builder().api_key(api_key).build()
     }
 
-    /// Set your database ID
-    pub fn database(&mut self, database_id: String) -> &mut Self {
-        self.database_id = database_id.to_string();
-        return self;
+    /// Create a [`NotionBuilder`] to configure the client
+    pub fn builder() -> NotionBuilder {
+        NotionBuilder::default()
     }
 
-    /// # Retrieve a database properties  
-    /// ## Return
-    /// - [`Database`] struct
-    pub async fn retrieve_a_database(&self) -> Result<Database> {
-        let url = format!("https://api.notion.com/v1/databases/{}", self.database_id);
-        let client = request::Client::new();
-        let content = client
-            .get(&url)
+    /// Root URL every endpoint is built from
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url, path)
+    }
+
+    /// Send a request and deserialize the response body.  
+    /// Failed requests are retried according to the [`RetryPolicy`]; `POST` and `PATCH` are
+    /// treated as writes.
+    async fn send<T: DeserializeOwned>(
+        &self,
+        method: request::Method,
+        url: &str,
+        body: Option<String>,
+    ) -> Result<T> {
+        let idempotent = !matches!(method, request::Method::POST | request::Method::PATCH);
+        self.send_with_retry(method, url, body, idempotent).await
+    }
+
+    /// Send a request, retrying failures according to the [`RetryPolicy`].  
+    /// `idempotent` tells whether the request is safe to repeat after it may have been applied.
+    async fn send_with_retry<T: DeserializeOwned>(
+        &self,
+        method: request::Method,
+        url: &str,
+        body: Option<String>,
+        idempotent: bool,
+    ) -> Result<T> {
+        let mut attempt = 1;
+        loop {
+            match self.send_once(method.clone(), url, body.clone()).await {
+                Err(e)
+                    if attempt < self.retry_policy.max_attempts
+                        && self.retry_policy.is_retryable_for(&e, idempotent) =>
+                {
+                    tokio::time::sleep(self.retry_policy.delay(attempt, &e)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Send a request once and deserialize the response body.  
+    /// Non-success responses are turned into [`NotionError::Api`].
+    async fn send_once<T: DeserializeOwned>(
+        &self,
+        method: request::Method,
+        url: &str,
+        body: Option<String>,
+    ) -> Result<T> {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire().await;
+        }
+        let mut builder = self
+            .client
+            .request(method, url)
             .header("Content-Type", "application/json")
             .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Notion-Version", "2022-06-28")
-            .send()
-            .await?
-            .text()
-            .await?;
+            .header("Notion-Version", "2022-06-28");
+        if let Some(body) = body {
+            builder = builder.body(body);
+        }
+        let response = builder.send().await?;
+        let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(request::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        let content = response.text().await?;
 
-        let mut database = serde_json::from_str::<Database>(&content)?;
-        if database.status == 0 {
-            database.status = 200;
+        if !status.is_success() {
+            return Err(NotionError::from_response(
+                status.as_u16(),
+                &content,
+                retry_after,
+            ));
         }
+        Ok(serde_json::from_str::<T>(&content)?)
+    }
 
+    /// # Retrieve a database properties  
+    /// ## Arguments:
+    /// - database_id: String
+    /// ## Return
+    /// - [`Database`] struct
+    pub async fn retrieve_a_database(&self, database_id: String) -> Result<Database> {
+        let url = self.url(&format!("databases/{}", database_id));
+        let mut database = self
+            .send::<Database>(request::Method::GET, &url, None)
+            .await?;
+        database.status = 200;
         return Ok(database);
     }
 
     /// # Query a database  
     /// ## Arguments:  
+    /// - database_id: String
     /// - filter: [`QueryFilter`]
     /// ## Return:  
     /// - [`PageResponse`] struct
-    pub async fn query_database(&self, filter: QueryFilter) -> Result<PageResponse> {
-        let url = format!(
-            "https://api.notion.com/v1/databases/{}/query",
-            self.database_id
-        );
+    pub async fn query_database(
+        &self,
+        database_id: String,
+        filter: QueryFilter,
+    ) -> Result<PageResponse> {
+        let mut url = request::Url::parse(&self.url(&format!("databases/{}/query", database_id)))
+            .map_err(|e| NotionError::InvalidUrl(e.to_string()))?;
+        if !filter.filter_properties.is_empty() {
+            let mut query = url.query_pairs_mut();
+            for property_id in &filter.filter_properties {
+                // property IDs are returned by the API already percent-encoded
+                query.append_pair("filter_properties", &percent_decode(property_id));
+            }
+        }
+        let url = url.to_string();
+        #[cfg(feature = "chrono")]
+        filter.validate_dates()?;
         let query = filter.build();
-        let client = request::Client::new();
-        let content = client
-            .post(&url)
-            .header("Content-Type", "application/json")
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Notion-Version", "2022-06-28")
-            .body(query)
-            .send()
-            .await?
-            .text()
+        let mut response = self
+            // a query is read-only, so it is retried like a GET
+            .send_with_retry::<PageResponse>(request::Method::POST, &url, Some(query), true)
             .await?;
-
-        let mut response = serde_json::from_str::<PageResponse>(&content)?;
-        if response.status != 0 {
-            return Err(Error::msg(
-                format!("Failed to query database: {}", response.message).to_string(),
-            ));
-        } else {
-            response.status = 200;
-        }
+        response.status = 200;
         return Ok(response);
     }
 
+    /// # Query a database, following pagination
+    /// Sends as many requests as needed, following `next_cursor` until every page has been returned.
+    /// ## Arguments:  
+    /// - database_id: String
+    /// - filter: [`QueryFilter`]
+    /// ## Return:  
+    /// - Stream of [`Page`] structs
+    pub fn query_database_stream(
+        &self,
+        database_id: String,
+        filter: QueryFilter,
+    ) -> impl Stream<Item = Result<Page>> {
+        let notion = self.clone();
+        stream::try_unfold(Some(filter), move |filter| {
+            let notion = notion.clone();
+            let database_id = database_id.clone();
+            async move {
+                let Some(mut filter) = filter else {
+                    return Ok::<_, NotionError>(None);
+                };
+                let response = notion.query_database(database_id, filter.clone()).await?;
+                let next_filter = match (response.has_more, response.next_cursor) {
+                    (Some(true), Some(next_cursor)) => {
+                        filter.start_cursor = next_cursor;
+                        Some(filter)
+                    }
+                    _ => None,
+                };
+                let pages = stream::iter(response.results.into_iter().map(Ok));
+                Ok(Some((pages, next_filter)))
+            }
+        })
+        .try_flatten()
+    }
+
+    /// # Query a database and collect every page
+    /// ## Arguments:  
+    /// - database_id: String
+    /// - filter: [`QueryFilter`]
+    /// ## Return:  
+    /// - Vec of [`Page`] structs
+    pub async fn query_database_all(
+        &self,
+        database_id: String,
+        filter: QueryFilter,
+    ) -> Result<Vec<Page>> {
+        self.query_database_stream(database_id, filter)
+            .try_collect()
+            .await
+    }
+
     /// # Retrieve a page
     /// ## Return
     /// - [`Page`] struct
     pub async fn retrieve_a_page(&self, page_id: String) -> Result<Page> {
-        let url = format!("https://api.notion.com/v1/pages/{}", page_id);
-        let client = request::Client::new();
-        let content = client
-            .get(&url)
-            .header("Content-Type", "application/json")
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Notion-Version", "2022-06-28")
-            .send()
-            .await?
-            .text()
-            .await?;
-
-        let mut page = serde_json::from_str::<Page>(&content)?;
-        if page.status == 0 {
-            page.status = 200;
-        }
-
+        let url = self.url(&format!("pages/{}", page_id));
+        let mut page = self.send::<Page>(request::Method::GET, &url, None).await?;
+        page.status = 200;
         return Ok(page);
     }
 
@@ -232,28 +467,14 @@ impl Notion {
     /// ## Return:
     /// - [`Page`] struct
     pub async fn create_a_page(&self, page: &Page) -> Result<Page> {
-        let url = "https://api.notion.com/v1/pages";
-        let client = request::Client::new();
+        #[cfg(feature = "chrono")]
+        page.validate_dates()?;
+        let url = self.url("pages");
         let data = serde_json::to_string(page)?;
-        let content = client
-            .post(url)
-            .header("Content-Type", "application/json")
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Notion-Version", "2022-06-28")
-            .body(data)
-            .send()
-            .await?
-            .text()
+        let mut page = self
+            .send::<Page>(request::Method::POST, &url, Some(data))
             .await?;
-
-        let mut page = serde_json::from_str::<Page>(&content)?;
-        if page.status != 0 {
-            return Err(Error::msg(
-                format!("Failed to create page: {}", page.message).to_string(),
-            ));
-        } else {
-            page.status = 200;
-        }
+        page.status = 200;
         return Ok(page);
     }
 
@@ -264,28 +485,14 @@ impl Notion {
     /// ## Return:
     /// - [`Page`] struct
     pub async fn update_a_page(&self, page_id: String, page: &Page) -> Result<Page> {
-        let url = format!("https://api.notion.com/v1/pages/{}", page_id);
-        let client = request::Client::new();
+        #[cfg(feature = "chrono")]
+        page.validate_dates()?;
+        let url = self.url(&format!("pages/{}", page_id));
         let data = serde_json::to_string(page)?;
-        let content = client
-            .patch(&url)
-            .header("Content-Type", "application/json")
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Notion-Version", "2022-06-28")
-            .body(data)
-            .send()
-            .await?
-            .text()
+        let mut page = self
+            .send::<Page>(request::Method::PATCH, &url, Some(data))
             .await?;
-
-        let mut page = serde_json::from_str::<Page>(&content)?;
-        if page.status != 0 {
-            return Err(Error::msg(
-                format!("Failed to update page: {}", page.message).to_string(),
-            ));
-        } else {
-            page.status = 200;
-        }
+        page.status = 200;
         return Ok(page);
     }
 
@@ -331,6 +538,155 @@ impl Notion {
         return Ok(page);
     }
 
+    /// # Retrieve a block
+    /// ## Arguments:
+    /// - block_id: String
+    /// ## Return:
+    /// - [`Block`] struct, without its children
+    pub async fn retrieve_a_block(&self, block_id: String) -> Result<Block> {
+        let url = self.url(&format!("blocks/{}", block_id));
+        self.send::<Block>(request::Method::GET, &url, None).await
+    }
+
+    /// # Update a block
+    /// Sends the type specific content of `block` (e.g. `to_do.checked` or `paragraph.rich_text`)
+    /// and `archived` when it is `true`. The block type cannot be changed, and nested children are
+    /// not updated. Use [`Notion::restore_a_block`] to un-archive a block.
+    /// ## Arguments:
+    /// - block_id: String
+    /// - block: [`Block`] struct
+    /// ## Return:
+    /// - [`Block`] struct
+    pub async fn update_a_block(&self, block_id: String, block: &Block) -> Result<Block> {
+        let url = self.url(&format!("blocks/{}", block_id));
+        let data = block_update_body(block)?;
+        self.send::<Block>(request::Method::PATCH, &url, Some(data))
+            .await
+    }
+
+    /// # Restore a block
+    /// Un-archives a block deleted with [`Notion::delete_a_block`].
+    /// ## Arguments:
+    /// - block_id: String
+    /// ## Return:
+    /// - [`Block`] struct, with `archived` set to `false`
+    pub async fn restore_a_block(&self, block_id: String) -> Result<Block> {
+        let url = self.url(&format!("blocks/{}", block_id));
+        let data = serde_json::json!({"archived": false}).to_string();
+        self.send::<Block>(request::Method::PATCH, &url, Some(data))
+            .await
+    }
+
+    /// # Delete a block
+    /// ## Arguments:
+    /// - block_id: String
+    /// ## Return:
+    /// - [`Block`] struct, with `archived` set to `true`
+    pub async fn delete_a_block(&self, block_id: String) -> Result<Block> {
+        let url = self.url(&format!("blocks/{}", block_id));
+        self.send::<Block>(request::Method::DELETE, &url, None)
+            .await
+    }
+
+    /// # Retrieve block children
+    /// Returns one page of up to 100 children. Use [`Notion::retrieve_block_children_stream`] or
+    /// [`Notion::retrieve_block_children_all`] to follow `next_cursor`.
+    /// ## Arguments:
+    /// - block_id: String, a block ID or a page ID
+    /// - start_cursor: `next_cursor` of the previous response, or `None` for the first page
+    /// ## Return:
+    /// - [`BlockResponse`] struct
+    pub async fn retrieve_block_children(
+        &self,
+        block_id: String,
+        start_cursor: Option<String>,
+    ) -> Result<BlockResponse> {
+        let mut url = self.url(&format!("blocks/{}/children", block_id));
+        if let Some(start_cursor) = start_cursor {
+            url = format!("{}?start_cursor={}", url, start_cursor);
+        }
+        let mut response = self
+            .send::<BlockResponse>(request::Method::GET, &url, None)
+            .await?;
+        response.status = 200;
+        Ok(response)
+    }
+
+    /// # Retrieve block children, following pagination
+    /// Sends as many requests as needed, following `next_cursor` until every child has been returned.
+    /// Only direct children are returned.
+    /// ## Arguments:
+    /// - block_id: String, a block ID or a page ID
+    /// ## Return:
+    /// - Stream of [`Block`] structs
+    pub fn retrieve_block_children_stream(
+        &self,
+        block_id: String,
+    ) -> impl Stream<Item = Result<Block>> {
+        let notion = self.clone();
+        stream::try_unfold(Some(None), move |start_cursor| {
+            let notion = notion.clone();
+            let block_id = block_id.clone();
+            async move {
+                let Some(start_cursor) = start_cursor else {
+                    return Ok::<_, NotionError>(None);
+                };
+                let response = notion
+                    .retrieve_block_children(block_id, start_cursor)
+                    .await?;
+                let next_cursor = match (response.has_more, response.next_cursor) {
+                    (Some(true), Some(next_cursor)) => Some(Some(next_cursor)),
+                    _ => None,
+                };
+                let blocks = stream::iter(response.results.into_iter().map(Ok));
+                Ok(Some((blocks, next_cursor)))
+            }
+        })
+        .try_flatten()
+    }
+
+    /// # Retrieve every child of a block
+    /// ## Arguments:
+    /// - block_id: String, a block ID or a page ID
+    /// ## Return:
+    /// - Vec of [`Block`] structs
+    pub async fn retrieve_block_children_all(&self, block_id: String) -> Result<Vec<Block>> {
+        self.retrieve_block_children_stream(block_id)
+            .try_collect()
+            .await
+    }
+
+    /// # Retrieve the content of a page
+    /// Fetches every block of the page, descending into blocks with `has_children` (toggles, list
+    /// items, columns, synced blocks, tables, ...) with a bounded number of concurrent requests.
+    /// Child pages and child databases are returned without their content.
+    /// ## Arguments:
+    /// - page_id: String, a page ID or a block ID
+    /// ## Return:
+    /// - Vec of top-level [`Block`] structs, with their descendants in [`Block::children`]
+    pub async fn retrieve_page_content(&self, page_id: String) -> Result<Vec<Block>> {
+        let mut blocks = self.retrieve_block_children_all(page_id).await?;
+        let mut children: FxHashMap<String, Vec<Block>> = FxHashMap::default();
+        let mut pending = blocks_to_expand(&blocks);
+        while !pending.is_empty() {
+            let fetched: Vec<(String, Vec<Block>)> = stream::iter(pending)
+                .map(|block_id| async move {
+                    let blocks = self.retrieve_block_children_all(block_id.clone()).await?;
+                    Ok::<_, NotionError>((block_id, blocks))
+                })
+                .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+                .try_collect()
+                .await?;
+            pending = fetched
+                .iter()
+                .flat_map(|(_, blocks)| blocks_to_expand(blocks))
+                .collect();
+            children.extend(fetched);
+        }
+        attach_children(&mut blocks, &mut children);
+        Ok(blocks)
+    }
+
     /// # Append block children
     /// Because the Notion API only allows appending 100 blocks at a time, this method will split the
     /// blocks into chunks of 100 and append them to the parent block.
@@ -344,35 +700,117 @@ impl Notion {
         parent_id: String,
         blocks: Vec<Block>,
     ) -> Result<BlockResponse> {
-        let url = format!("https://api.notion.com/v1/blocks/{}/children", parent_id);
-        let client = request::Client::new();
+        self.append_block_children_after(parent_id, blocks, None)
+            .await
+    }
+
+    /// # Append block children, including deeply nested ones
+    /// Notion accepts two levels of blocks, at most 100 children per list and at most 1000 blocks in
+    /// one request. This method sends the blocks with as many of their direct children as fit, then
+    /// appends deeper descendants (and the children that did not fit) to the created blocks, so trees
+    /// of any depth can be uploaded.
+    /// Nested blocks are read from the type specific `children` (e.g. [`ToggleBlock::children`]) and
+    /// from [`Block::children`]. Tables, column lists and columns are always sent with the children
+    /// Notion needs to create them.
+    /// ## Arguments:
+    /// - parent_id: String
+    /// - blocks: Vec of [`Block`] structs
+    /// ## Return:
+    /// - [`BlockResponse`] struct with the top-level blocks
+    pub async fn append_block_children_deep(
+        &self,
+        parent_id: String,
+        blocks: Vec<Block>,
+    ) -> Result<BlockResponse> {
+        let mut chunks: Vec<Vec<Block>> = Vec::new();
+        let mut chunk: Vec<Block> = Vec::new();
+        let mut chunk_size = 0;
+        let mut deferred = Vec::with_capacity(blocks.len());
+        for mut block in blocks {
+            let required = required_size(&mut block);
+            if chunk.len() == 100
+                || (!chunk.is_empty() && chunk_size + required > MAX_BLOCKS_PER_REQUEST)
+            {
+                chunks.push(std::mem::take(&mut chunk));
+                chunk_size = 0;
+            }
+            let (left, size) = split_nested_children(
+                &mut block,
+                MAX_NESTING_PER_REQUEST,
+                MAX_BLOCKS_PER_REQUEST - chunk_size,
+            );
+            deferred.push(left);
+            chunk_size += size;
+            chunk.push(block);
+        }
+        chunks.push(chunk);
+
+        let mut res_blocks: Vec<Block> = Vec::new();
+        for chunk in chunks {
+            let response = self.append_block_children(parent_id.clone(), chunk).await?;
+            res_blocks.extend(response.results);
+        }
+        let response = BlockResponse {
+            object: "list".to_string(),
+            results: res_blocks,
+            status: 200,
+            ..Default::default()
+        };
+        for (block, left) in response.results.iter().zip(deferred) {
+            self.append_deferred(block.id.clone(), left).await?;
+        }
+        Ok(response)
+    }
+
+    /// Append what [`split_nested_children`] left out to the created block `block_id`
+    async fn append_deferred(&self, block_id: String, deferred: Deferred) -> Result<()> {
+        if !deferred.nested.iter().all(Deferred::is_empty) {
+            let inline = self.retrieve_block_children_all(block_id.clone()).await?;
+            for (child, left) in inline.iter().zip(deferred.nested) {
+                Box::pin(self.append_deferred(child.id.clone(), left)).await?;
+            }
+        }
+        if !deferred.children.is_empty() {
+            Box::pin(self.append_block_children_deep(block_id, deferred.children)).await?;
+        }
+        Ok(())
+    }
+
+    /// # Append block children after a block
+    /// Same as [`Notion::append_block_children`], but inserts the blocks after the child block
+    /// `after` instead of at the end of the parent. Each chunk of 100 blocks is inserted after the
+    /// last block of the previous chunk, so the blocks keep their order.
+    /// ## Arguments:
+    /// - parent_id: String
+    /// - blocks: [`BlockBody`]
+    /// - after: ID of an existing child of `parent_id`, or `None` to append at the end
+    /// ## Return:
+    /// - [`BlockResponse`] struct
+    pub async fn append_block_children_after(
+        &self,
+        parent_id: String,
+        blocks: Vec<Block>,
+        mut after: Option<String>,
+    ) -> Result<BlockResponse> {
+        let url = self.url(&format!("blocks/{}/children", parent_id));
         let mut res_blocks: Vec<Block> = Vec::new();
 
         for i in (0..blocks.len()).step_by(100) {
             let end_index = std::cmp::min(i + 100, blocks.len());
             let block_body = BlockBody {
                 children: blocks[i..end_index].to_vec(),
+                after: after.clone(),
             };
             let data = serde_json::to_string(&block_body)?;
-            let content = client
-                .patch(&url)
-                .header("Content-Type", "application/json")
-                .header("Authorization", format!("Bearer {}", self.api_key))
-                .header("Notion-Version", "2022-06-28")
-                .body(data)
-                .send()
-                .await?
-                .text()
+            let response = self
+                .send::<BlockResponse>(request::Method::PATCH, &url, Some(data))
                 .await?;
-            let _bby = serde_json::from_str::<BlockResponse>(&content)?;
-
-            if _bby.status != 0 {
-                return Err(Error::msg(
-                    format!("Failed to append block children: {}", _bby.message).to_string(),
-                ));
-            } else {
-                res_blocks.extend(_bby.results);
+            if after.is_some() {
+                if let Some(last) = response.results.last() {
+                    after = Some(last.id.clone());
+                }
             }
+            res_blocks.extend(response.results);
         }
 
         let res_block = BlockResponse {
@@ -385,5 +823,150 @@ impl Notion {
     }
 }
 
+/// IDs of the blocks whose children are part of the page content
+fn blocks_to_expand(blocks: &[Block]) -> Vec<String> {
+    blocks
+        .iter()
+        .filter(|block| {
+            block.has_children
+                && !matches!(block.type_name.as_str(), "child_page" | "child_database")
+        })
+        .map(|block| block.id.clone())
+        .collect()
+}
+
+/// Move fetched children into the tree, depth first
+fn attach_children(blocks: &mut [Block], children: &mut FxHashMap<String, Vec<Block>>) {
+    for block in blocks {
+        if let Some(mut nested) = children.remove(&block.id) {
+            attach_children(&mut nested, children);
+            block.children = nested;
+        }
+    }
+}
+
+/// Blocks to append once their parent block has been created
+#[derive(Debug, Default)]
+struct Deferred {
+    /// Children to append to the block itself
+    children: Vec<Block>,
+    /// What is left for each inline child, by position
+    nested: Vec<Deferred>,
+}
+
+impl Deferred {
+    fn is_empty(&self) -> bool {
+        self.children.is_empty() && self.nested.iter().all(Deferred::is_empty)
+    }
+}
+
+/// Number of blocks Notion needs in the same request to create `block`
+fn required_size(block: &mut Block) -> usize {
+    if block.required_depth() == 0 {
+        1
+    } else {
+        1 + block.nested_children_mut().map_or(0, |inline| {
+            inline.iter_mut().take(100).map(required_size).sum()
+        })
+    }
+}
+
+/// Keeps the direct children of `block` inline when Notion accepts them in the same request and
+/// there is `room` for them. Only blocks that cannot be created empty (tables, column lists and
+/// columns) keep their children `levels` deep, and they keep as many as fit.
+/// Returns what is left to append afterwards and the number of blocks sent, `block` included.
+fn split_nested_children(block: &mut Block, levels: usize, room: usize) -> (Deferred, usize) {
+    let mut children = std::mem::take(&mut block.children);
+    let required = block.required_depth() > 0;
+    let limit = room.saturating_sub(1).min(100);
+    let Some(inline) = block.nested_children_mut() else {
+        return (
+            Deferred {
+                children,
+                nested: Vec::new(),
+            },
+            1,
+        );
+    };
+    let fits = levels > 0
+        && inline.iter().all(|child| child.required_depth() < levels)
+        && (required || inline.len() <= limit);
+    if !fits {
+        block.children = children;
+        return (
+            Deferred {
+                children: block.take_children(),
+                nested: Vec::new(),
+            },
+            1,
+        );
+    }
+
+    let mut rest = inline.split_off(inline.len().min(limit));
+    rest.append(&mut children);
+    let mut size = 1 + inline.len();
+    let nested = inline
+        .iter_mut()
+        .map(|child| {
+            let levels = if child.required_depth() > 0 {
+                levels - 1
+            } else {
+                0
+            };
+            let (left, child_size) = split_nested_children(child, levels, room - size + 1);
+            size += child_size - 1;
+            left
+        })
+        .collect();
+    (
+        Deferred {
+            children: rest,
+            nested,
+        },
+        size,
+    )
+}
+
+/// Decode `%XX` escapes, keeping a `%` that does not start one as is
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Request body of "Update a block": only the type specific object and `archived` are accepted
+fn block_update_body(block: &Block) -> Result<String> {
+    let mut body = serde_json::to_value(block)?;
+    if let Some(body) = body.as_object_mut() {
+        body.remove("object");
+        body.remove("type");
+        if let Some(content) = body
+            .get_mut(&block.type_name)
+            .and_then(|content| content.as_object_mut())
+        {
+            content.remove("children");
+        }
+    }
+    Ok(body.to_string())
+}
+
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,103 @@
+//! # Rate limiter
+//!
+//! The Notion API allows an average of three requests per second per integration. A
+//! [`RateLimiter`] throttles requests on the client side with a token bucket, so many tasks can
+//! share one client without tripping the limit. Clones of a [`RateLimiter`] (and of a
+//! [`Notion`](crate::Notion) client using it) share the same bucket.
+//!
+//! ```rust
+//! # use notion_tools::Notion;
+//! # use notion_tools::rate_limit::RateLimiter;
+//! # #[tokio::main]
+//! # async fn main() {
+//! let notion = Notion::builder()
+//!     .api_key(String::from("secret_xxx"))
+//!     .rate_limiter(RateLimiter::new(3.0))
+//!     .build();
+//!
+//! for _ in 0..10 {
+//!     let notion = notion.clone();
+//!     tokio::spawn(async move {
+//!         // every task draws from the same budget of 3 requests per second
+//!         # let _ = notion;
+//!     });
+//! }
+//! # }
+//! ```
+//!
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Token bucket shared by every clone
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// Tokens added per second
+    rate: f64,
+    /// Maximum number of tokens
+    capacity: f64,
+    /// Available tokens; negative when requests are queued
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Allow `requests_per_second` requests on average, without bursts
+    /// # Panics
+    /// If `requests_per_second` is not a positive finite number
+    pub fn new(requests_per_second: f64) -> Self {
+        RateLimiter::with_burst(requests_per_second, 1)
+    }
+
+    /// Allow `requests_per_second` requests on average and up to `burst` requests at once
+    /// # Panics
+    /// If `requests_per_second` is not a positive finite number
+    pub fn with_burst(requests_per_second: f64, burst: u32) -> Self {
+        assert!(
+            requests_per_second.is_finite() && requests_per_second > 0.0,
+            "requests_per_second must be positive and finite"
+        );
+        let capacity = burst.max(1) as f64;
+        RateLimiter {
+            bucket: Arc::new(Mutex::new(Bucket {
+                rate: requests_per_second,
+                capacity,
+                tokens: capacity,
+                last_refill: Instant::now(),
+            })),
+        }
+    }
+
+    /// Wait until a request may be sent
+    pub async fn acquire(&self) {
+        let wait = self.reserve();
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Take a token and return how long the caller has to wait before using it
+    pub(crate) fn reserve(&self) -> Duration {
+        self.reserve_at(Instant::now())
+    }
+
+    /// [`RateLimiter::reserve`] at the instant `now`
+    pub(crate) fn reserve_at(&self, now: Instant) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+        let now = now.max(bucket.last_refill);
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * bucket.rate).min(bucket.capacity);
+        bucket.last_refill = now;
+        bucket.tokens -= 1.0;
+
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::try_from_secs_f64(-bucket.tokens / bucket.rate).unwrap_or(Duration::MAX)
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! # Retry policy
+//!
+//! The Notion API rate-limits integrations and occasionally answers with `5xx` errors. A
+//! [`RetryPolicy`] tells the [`Notion`](crate::Notion) client which failures to retry and how long to
+//! wait in between. It is applied to every request the client sends.
+//!
+//! Writes (`POST` and `PATCH`, e.g. creating a page or appending blocks) may already have been
+//! applied when a transport error or a `5xx` is returned, so by default they are only retried after
+//! `rate_limited`, which Notion answers before doing anything. Set
+//! [`RetryPolicy::retry_non_idempotent`] to retry them like reads. Database queries are read-only
+//! and are retried like reads.
+//!
+//! ```rust
+//! # use notion_tools::Notion;
+//! # use notion_tools::error::ErrorCode;
+//! # use notion_tools::retry::RetryPolicy;
+//! # use std::time::Duration;
+//! let notion = Notion::builder()
+//!     .api_key(String::from("secret_xxx"))
+//!     .retry_policy(RetryPolicy {
+//!         max_attempts: 6,
+//!         base_delay: Duration::from_secs(1),
+//!         retryable_codes: vec![ErrorCode::RateLimited, ErrorCode::ConflictError],
+//!         ..Default::default()
+//!     })
+//!     .build();
+//! ```
+//!
+use crate::error::{ErrorCode, NotionError};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// Retry policy applied to every request sent by [`Notion`](crate::Notion)
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts including the first one. `1` disables retries.
+    pub max_attempts: u32,
+    /// Delay before the first retry. It is doubled on every further retry.
+    pub base_delay: Duration,
+    /// Upper bound of the delay before a retry, including a delay asked for with `Retry-After`
+    pub max_delay: Duration,
+    /// Fraction of the backoff (`0.0..=1.0`) that is randomized to spread out concurrent retries
+    pub jitter: f64,
+    /// API error codes that are retried
+    pub retryable_codes: Vec<ErrorCode>,
+    /// Retry when the request could not be sent or the response could not be read
+    pub retry_transport_errors: bool,
+    /// Retry writes (`POST` and `PATCH`) on every retryable error, not only on `rate_limited`.
+    /// A retried write may be applied twice, e.g. creating duplicate pages or blocks.
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: 0.2,
+            retryable_codes: vec![
+                ErrorCode::RateLimited,
+                ErrorCode::InternalServerError,
+                ErrorCode::BadGateway,
+                ErrorCode::ServiceUnavailable,
+                ErrorCode::DatabaseConnectionUnavailable,
+                ErrorCode::GatewayTimeout,
+            ],
+            retry_transport_errors: true,
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Whether `error` should be retried under this policy, for a request that is safe to repeat
+    pub fn is_retryable(&self, error: &NotionError) -> bool {
+        match error {
+            NotionError::Api { code, .. } => self.retryable_codes.contains(code),
+            NotionError::Http(_) => self.retry_transport_errors,
+            _ => false,
+        }
+    }
+
+    /// Whether `error` should be retried under this policy.
+    /// Unless [`RetryPolicy::retry_non_idempotent`] is set, a request that is not `idempotent` is
+    /// only retried after `rate_limited`.
+    pub fn is_retryable_for(&self, error: &NotionError, idempotent: bool) -> bool {
+        if idempotent || self.retry_non_idempotent {
+            self.is_retryable(error)
+        } else {
+            matches!(
+                error,
+                NotionError::Api {
+                    code: ErrorCode::RateLimited,
+                    ..
+                }
+            ) && self.is_retryable(error)
+        }
+    }
+
+    /// Delay before retry number `retry` (starting at 1) after `error`.
+    /// The `Retry-After` header sent with the error takes precedence over the backoff, up to
+    /// [`RetryPolicy::max_delay`].
+    pub fn delay(&self, retry: u32, error: &NotionError) -> Duration {
+        if let NotionError::Api {
+            retry_after: Some(retry_after),
+            ..
+        } = error
+        {
+            return (*retry_after).min(self.max_delay);
+        }
+
+        let exponent = retry.saturating_sub(1).min(31);
+        let backoff = self
+            .base_delay
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay);
+        let jitter = self.jitter.clamp(0.0, 1.0) * random_fraction();
+        backoff.mul_f64(1.0 - jitter)
+    }
+}
+
+/// Random number in `0.0..1.0`, good enough to de-synchronize retries
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(0);
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
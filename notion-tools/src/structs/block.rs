@@ -28,6 +28,8 @@ pub struct Callout {
     pub icon: Option<Icon>,
     #[serde(default = "Color::default")]
     pub color: Color,
+    #[serde(default = "Vec::default", skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Block>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -52,6 +54,20 @@ pub struct Code {
     pub language: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Column {
+    /// Content of the column, at least one block when the column is created
+    #[serde(default = "Vec::default", skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Block>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ColumnList {
+    /// Columns, at least two when the column list is created
+    #[serde(default = "Vec::default", skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Block>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Embed {
     #[serde(default = "String::new")]
@@ -72,6 +88,9 @@ pub struct Heading {
     pub color: Color,
     #[serde(default = "bool::default")]
     pub is_toggleable: bool,
+    /// Only toggleable headings have children
+    #[serde(default = "Vec::default", skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Block>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -126,6 +145,23 @@ pub struct Paragraph {
     pub children: Vec<Block>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SyncedFrom {
+    #[serde(rename = "type", default = "String::new")]
+    pub type_name: String,
+    #[serde(default = "String::new")]
+    pub block_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SyncedBlock {
+    /// `None` for an original synced block, the original block for a duplicate
+    #[serde(default = "Option::default")]
+    pub synced_from: Option<SyncedFrom>,
+    #[serde(default = "Vec::default", skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Block>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Table {
     #[serde(default = "u32::default")]
@@ -134,6 +170,9 @@ pub struct Table {
     pub has_column_header: bool,
     #[serde(default = "bool::default")]
     pub has_row_header: bool,
+    /// Rows of the table, which must be sent with it when the table is created
+    #[serde(default = "Vec::default", skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Block>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -205,6 +244,11 @@ pub struct Block {
     pub has_children: bool,
     #[serde(default = "bool::default", skip_serializing)]
     pub is_togglable: bool,
+    /// Nested blocks, filled in by [`Notion::retrieve_page_content`](crate::Notion::retrieve_page_content).
+    /// Never sent to the API: [`Notion::append_block_children_deep`](crate::Notion::append_block_children_deep)
+    /// appends them with their own requests.
+    #[serde(default = "Vec::default", skip_serializing)]
+    pub children: Vec<Block>,
 
     // type specific properties
     #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
@@ -222,9 +266,9 @@ pub struct Block {
     #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
     pub code: Option<Code>,
     #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
-    pub column_list: Option<FxHashMap<String, String>>,
+    pub column_list: Option<ColumnList>,
     #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
-    pub column: Option<FxHashMap<String, String>>,
+    pub column: Option<Column>,
     #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
     pub divider: Option<FxHashMap<String, String>>,
     #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
@@ -252,6 +296,8 @@ pub struct Block {
     #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
     pub quote: Option<Quote>,
     #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub synced_block: Option<SyncedBlock>,
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
     pub table: Option<Table>,
     #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
     pub table_row: Option<TableRow>,
@@ -286,6 +332,7 @@ impl Default for Block {
             type_name: "".to_string(),
             has_children: false,
             is_togglable: false,
+            children: Vec::new(),
             bookmark: None,
             breadcrumb: None,
             bulleted_list_item: None,
@@ -308,6 +355,7 @@ impl Default for Block {
             paragraph: None,
             pdf: None,
             quote: None,
+            synced_block: None,
             table: None,
             table_row: None,
             table_of_contents: None,
@@ -383,6 +431,7 @@ impl Block {
             rich_text: rich_text,
             icon: Some(icon),
             color: color,
+            ..Default::default()
         };
         let block = Block {
             parent: Parent::from(parent_type, parent_id),
@@ -449,7 +498,7 @@ impl Block {
         let block = Block {
             parent: Parent::from(parent_type, parent_id),
             type_name: "column_list".to_string(),
-            column_list: Some(FxHashMap::default()),
+            column_list: Some(ColumnList::default()),
             ..Default::default()
         };
         return block;
@@ -459,7 +508,7 @@ impl Block {
         let block = Block {
             parent: Parent::from(parent_type, parent_id),
             type_name: "column".to_string(),
-            column: Some(FxHashMap::default()),
+            column: Some(Column::default()),
             ..Default::default()
         };
         return block;
@@ -670,6 +719,16 @@ impl Block {
         return block;
     }
 
+    /// Original synced block, whose content is set in [`SyncedBlock::children`]
+    pub fn synced_block(parent_type: ParentType, parent_id: String) -> Self {
+        Block {
+            parent: Parent::from(parent_type, parent_id),
+            type_name: "synced_block".to_string(),
+            synced_block: Some(SyncedBlock::default()),
+            ..Default::default()
+        }
+    }
+
     pub fn table(parent_type: ParentType, parent_id: String) -> Self {
         let block = Block {
             parent: Parent::from(parent_type, parent_id),
@@ -763,6 +822,47 @@ impl Block {
         };
         return block;
     }
+
+    /// Children nested in the type specific object, which are sent along with the block
+    pub(crate) fn nested_children_mut(&mut self) -> Option<&mut Vec<Block>> {
+        match self.type_name.as_str() {
+            "bulleted_list_item" => self.bulleted_list_item.as_mut().map(|b| &mut b.children),
+            "numbered_list_item" => self.numbered_list_item.as_mut().map(|b| &mut b.children),
+            "paragraph" => self.paragraph.as_mut().map(|b| &mut b.children),
+            "quote" => self.quote.as_mut().map(|b| &mut b.children),
+            "to_do" => self.to_do.as_mut().map(|b| &mut b.children),
+            "toggle" => self.toggle.as_mut().map(|b| &mut b.children),
+            "callout" => self.callout.as_mut().map(|b| &mut b.children),
+            "column_list" => self.column_list.as_mut().map(|b| &mut b.children),
+            "column" => self.column.as_mut().map(|b| &mut b.children),
+            "heading_1" => self.heading_1.as_mut().map(|b| &mut b.children),
+            "heading_2" => self.heading_2.as_mut().map(|b| &mut b.children),
+            "heading_3" => self.heading_3.as_mut().map(|b| &mut b.children),
+            "synced_block" => self.synced_block.as_mut().map(|b| &mut b.children),
+            "table" => self.table.as_mut().map(|b| &mut b.children),
+            _ => None,
+        }
+    }
+
+    /// Levels of children Notion requires when the block is created: a table needs its rows, a
+    /// column its content, and a column list its columns with their content
+    pub(crate) fn required_depth(&self) -> usize {
+        match self.type_name.as_str() {
+            "table" | "column" => 1,
+            "column_list" => 2,
+            _ => 0,
+        }
+    }
+
+    /// Removes every nested child of the block: the type specific ones first, then [`Block::children`]
+    pub(crate) fn take_children(&mut self) -> Vec<Block> {
+        let mut children = self
+            .nested_children_mut()
+            .map(std::mem::take)
+            .unwrap_or_default();
+        children.append(&mut self.children);
+        children
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -777,10 +877,16 @@ pub struct BlockResponse {
     pub message: String,
     #[serde(default = "Vec::new")]
     pub results: Vec<Block>,
+    #[serde(default = "Option::default")]
+    pub has_more: Option<bool>,
+    #[serde(default = "Option::default")]
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct BlockBody {
     #[serde(default = "Vec::default")]
     pub children: Vec<Block>,
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
 }
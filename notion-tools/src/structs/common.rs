@@ -58,10 +58,131 @@ impl Icon {
     }
 }
 
+/// An ISO 8601 date (`2024-12-20`) or date-time (`2024-12-20T10:00:00.000+09:00`) sent to the Notion API.  
+/// Strings are used as-is. With the `chrono` feature, `chrono::NaiveDate`, `chrono::NaiveDateTime`
+/// and `chrono::DateTime` are converted too.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DateValue(String);
+
+impl DateValue {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl From<String> for DateValue {
+    fn from(value: String) -> Self {
+        DateValue(value)
+    }
+}
+
+impl From<&String> for DateValue {
+    fn from(value: &String) -> Self {
+        DateValue(value.to_string())
+    }
+}
+
+impl From<&str> for DateValue {
+    fn from(value: &str) -> Self {
+        DateValue(value.to_string())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveDate> for DateValue {
+    fn from(value: chrono::NaiveDate) -> Self {
+        DateValue(value.format("%Y-%m-%d").to_string())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveDateTime> for DateValue {
+    fn from(value: chrono::NaiveDateTime) -> Self {
+        DateValue(value.format("%Y-%m-%dT%H:%M:%S%.3f").to_string())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> From<chrono::DateTime<Tz>> for DateValue
+where
+    Tz::Offset: std::fmt::Display,
+{
+    fn from(value: chrono::DateTime<Tz>) -> Self {
+        DateValue(value.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+    }
+}
+
+/// Check that `value` is an ISO 8601 date or date-time accepted by the Notion API
+#[cfg(feature = "chrono")]
+pub fn validate_date(value: &str) -> crate::error::Result<()> {
+    let is_valid = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok()
+        || chrono::DateTime::parse_from_rfc3339(value).is_ok()
+        || chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f").is_ok()
+        || chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M").is_ok();
+    if is_valid {
+        Ok(())
+    } else {
+        Err(crate::error::NotionError::InvalidDate(value.to_string()))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Date {
     #[serde(default = "String::new")]
     pub start: String,
+    /// End of a date range
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+    /// IANA time zone, e.g. `Asia/Tokyo`, applied to `start` and `end` when they have no offset
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub time_zone: Option<String>,
+}
+
+impl Date {
+    pub fn new(start: impl Into<DateValue>) -> Self {
+        Date {
+            start: start.into().into_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn range(start: impl Into<DateValue>, end: impl Into<DateValue>) -> Self {
+        Date {
+            start: start.into().into_string(),
+            end: Some(end.into().into_string()),
+            ..Default::default()
+        }
+    }
+
+    /// A date-time without offset, interpreted in `time_zone`
+    pub fn zoned(start: impl Into<DateValue>, time_zone: impl Into<String>) -> Self {
+        Date::new(start).with_time_zone(time_zone)
+    }
+
+    /// Interpret the date-times of the date (or range) in `time_zone`, e.g.
+    /// `Date::range(start, end).with_time_zone("Asia/Tokyo")`
+    pub fn with_time_zone(self, time_zone: impl Into<String>) -> Self {
+        Date {
+            time_zone: Some(time_zone.into()),
+            ..self
+        }
+    }
+
+    /// Render the date as `start`, or `start → end` for ranges, followed by the time zone if any
+    pub fn get_value(&self) -> String {
+        let mut value = self.start.to_string();
+        if let Some(end) = &self.end {
+            value = format!("{} → {}", value, end);
+        }
+        if let Some(time_zone) = &self.time_zone {
+            value = format!("{} ({})", value, time_zone);
+        }
+        value
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -102,6 +223,14 @@ pub struct Formula {
     pub string: String,
 }
 
+/// Verification state of a page in a wiki database
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Verification {
+    /// `verified`, `unverified` or `expired`
+    #[serde(default = "String::new")]
+    pub state: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct PhoneNumber {
     #[serde(default = "String::new")]
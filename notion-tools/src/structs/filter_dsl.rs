@@ -0,0 +1,682 @@
+//! # Filter DSL
+//!
+//! A typed shorthand for building [`FilterItem`] trees. [`prop`] names a property, the next call
+//! picks its type and the last one the condition. Conditions are combined with `&` (and) and
+//! `|` (or); chained operators are flattened into a single `and` or `or` group, and empty items
+//! are dropped, so a filter can be folded from `FilterItem::default()`.
+//! [`timestamp`] starts a condition on the page's `created_time` or `last_edited_time`.
+//!
+//! ```rust
+//! # fn main() {
+//! # use notion_tools::structs::filter_dsl::prop;
+//! # use notion_tools::structs::query_filter::QueryFilter;
+//! let mut query_filter = QueryFilter::new();
+//! query_filter.args(
+//!     prop("Status").status().eq("Active")
+//!         & (prop("Age").number().gt(18) | prop("Address").text().contains("New York")),
+//! );
+//! let filter = query_filter.build();
+//! # }
+//! ```
+//!
+//! This is the same filter as
+//!
+//! ```rust
+//! # fn main() {
+//! # use notion_tools::structs::query_filter::*;
+//! FilterItem::and(vec![
+//!    FilterItem::status(String::from("Status"), StatusFilterItem::equals(String::from("Active"))),
+//!    FilterItem::or(vec![
+//!       FilterItem::number(String::from("Age"), NumberFilterItem::greater_than(18)),
+//!       FilterItem::rich_text(String::from("Address"), RichTextFilterItem::contains(String::from("New York"))),
+//!    ]),
+//! ]);
+//! # }
+//! ```
+//!
+use crate::structs::common::DateValue;
+use crate::structs::query_filter::*;
+use std::ops::{BitAnd, BitOr};
+
+/// Start a condition on the property `name`
+pub fn prop(name: impl Into<String>) -> Property {
+    Property { name: name.into() }
+}
+
+/// Start a condition on the creation or last edit time of the page, e.g.
+/// `timestamp().created_time().past_week()`
+pub fn timestamp() -> Timestamp {
+    Timestamp
+}
+
+/// A page timestamp whose kind is not chosen yet
+#[derive(Debug, Clone)]
+pub struct Timestamp;
+
+impl Timestamp {
+    pub fn created_time(self) -> DateCondition {
+        DateCondition {
+            property: String::new(),
+            wrap: |property, item| {
+                FilterItem::timestamp(property, TimestampFilterItem::created_time(item))
+            },
+        }
+    }
+
+    pub fn last_edited_time(self) -> DateCondition {
+        DateCondition {
+            property: String::new(),
+            wrap: |property, item| {
+                FilterItem::timestamp(property, TimestampFilterItem::last_edited_time(item))
+            },
+        }
+    }
+}
+
+/// A property whose type is not chosen yet
+#[derive(Debug, Clone)]
+pub struct Property {
+    name: String,
+}
+
+impl Property {
+    pub fn checkbox(self) -> CheckboxCondition {
+        CheckboxCondition {
+            property: self.name,
+            wrap: FilterItem::checkbox,
+        }
+    }
+
+    pub fn date(self) -> DateCondition {
+        DateCondition {
+            property: self.name,
+            wrap: FilterItem::date,
+        }
+    }
+
+    pub fn files(self) -> FilesCondition {
+        FilesCondition {
+            property: self.name,
+        }
+    }
+
+    pub fn formula(self) -> FormulaCondition {
+        FormulaCondition {
+            property: self.name,
+        }
+    }
+
+    pub fn multi_select(self) -> MultiSelectCondition {
+        MultiSelectCondition {
+            property: self.name,
+        }
+    }
+
+    pub fn number(self) -> NumberCondition {
+        NumberCondition {
+            property: self.name,
+            wrap: FilterItem::number,
+        }
+    }
+
+    pub fn people(self) -> PeopleCondition {
+        PeopleCondition {
+            property: self.name,
+            wrap: FilterItem::people,
+        }
+    }
+
+    pub fn created_by(self) -> PeopleCondition {
+        PeopleCondition {
+            property: self.name,
+            wrap: FilterItem::created_by,
+        }
+    }
+
+    pub fn last_edited_by(self) -> PeopleCondition {
+        PeopleCondition {
+            property: self.name,
+            wrap: FilterItem::last_edited_by,
+        }
+    }
+
+    pub fn relation(self) -> RelationCondition {
+        RelationCondition {
+            property: self.name,
+        }
+    }
+
+    pub fn rollup(self) -> RollupCondition {
+        RollupCondition {
+            property: self.name,
+        }
+    }
+
+    /// Rich text property, including `title`, `url`, `email` and `phone_number` properties
+    pub fn text(self) -> TextCondition {
+        TextCondition {
+            property: self.name,
+            wrap: FilterItem::rich_text,
+        }
+    }
+
+    pub fn select(self) -> SelectCondition {
+        SelectCondition {
+            property: self.name,
+        }
+    }
+
+    pub fn status(self) -> StatusCondition {
+        StatusCondition {
+            property: self.name,
+        }
+    }
+
+    pub fn unique_id(self) -> UniqueIdCondition {
+        UniqueIdCondition {
+            property: self.name,
+            wrap: FilterItem::unique_id,
+        }
+    }
+
+    /// A `unique_id` property, filtered with the `id` filter
+    pub fn id(self) -> UniqueIdCondition {
+        UniqueIdCondition {
+            property: self.name,
+            wrap: FilterItem::id,
+        }
+    }
+
+    pub fn verification(self) -> VerificationCondition {
+        VerificationCondition {
+            property: self.name,
+        }
+    }
+}
+
+pub struct CheckboxCondition {
+    property: String,
+    wrap: fn(String, CheckboxFilterItem) -> FilterItem,
+}
+
+impl CheckboxCondition {
+    pub fn eq(self, value: bool) -> FilterItem {
+        (self.wrap)(self.property, CheckboxFilterItem::is(value))
+    }
+
+    pub fn ne(self, value: bool) -> FilterItem {
+        (self.wrap)(self.property, CheckboxFilterItem::is_not(value))
+    }
+}
+
+pub struct DateCondition {
+    property: String,
+    wrap: fn(String, DateFilterItem) -> FilterItem,
+}
+
+impl DateCondition {
+    fn build(self, item: DateFilterItem) -> FilterItem {
+        (self.wrap)(self.property, item)
+    }
+
+    pub fn eq(self, value: impl Into<DateValue>) -> FilterItem {
+        self.build(DateFilterItem::equals(value))
+    }
+
+    pub fn before(self, value: impl Into<DateValue>) -> FilterItem {
+        self.build(DateFilterItem::before(value))
+    }
+
+    pub fn after(self, value: impl Into<DateValue>) -> FilterItem {
+        self.build(DateFilterItem::after(value))
+    }
+
+    pub fn on_or_before(self, value: impl Into<DateValue>) -> FilterItem {
+        self.build(DateFilterItem::on_or_before(value))
+    }
+
+    pub fn on_or_after(self, value: impl Into<DateValue>) -> FilterItem {
+        self.build(DateFilterItem::on_or_after(value))
+    }
+
+    pub fn is_empty(self) -> FilterItem {
+        self.build(DateFilterItem::is_empty())
+    }
+
+    pub fn is_not_empty(self) -> FilterItem {
+        self.build(DateFilterItem::is_not_empty())
+    }
+
+    pub fn past_week(self) -> FilterItem {
+        self.build(DateFilterItem::past_week())
+    }
+
+    pub fn past_month(self) -> FilterItem {
+        self.build(DateFilterItem::past_month())
+    }
+
+    pub fn past_year(self) -> FilterItem {
+        self.build(DateFilterItem::past_year())
+    }
+
+    pub fn next_week(self) -> FilterItem {
+        self.build(DateFilterItem::next_week())
+    }
+
+    pub fn next_month(self) -> FilterItem {
+        self.build(DateFilterItem::next_month())
+    }
+
+    pub fn next_year(self) -> FilterItem {
+        self.build(DateFilterItem::next_year())
+    }
+
+    pub fn this_week(self) -> FilterItem {
+        self.build(DateFilterItem::this_week())
+    }
+}
+
+pub struct FilesCondition {
+    property: String,
+}
+
+impl FilesCondition {
+    pub fn is_empty(self) -> FilterItem {
+        FilterItem::files(self.property, FilesFilterItem::is_empty())
+    }
+
+    pub fn is_not_empty(self) -> FilterItem {
+        FilterItem::files(self.property, FilesFilterItem::is_not_empty())
+    }
+}
+
+/// A formula property; the next call picks the type of the formula result
+pub struct FormulaCondition {
+    property: String,
+}
+
+impl FormulaCondition {
+    pub fn checkbox(self) -> CheckboxCondition {
+        CheckboxCondition {
+            property: self.property,
+            wrap: |property, item| FilterItem::formula(property, FormulaFilterItem::checkbox(item)),
+        }
+    }
+
+    pub fn date(self) -> DateCondition {
+        DateCondition {
+            property: self.property,
+            wrap: |property, item| FilterItem::formula(property, FormulaFilterItem::date(item)),
+        }
+    }
+
+    pub fn number(self) -> NumberCondition {
+        NumberCondition {
+            property: self.property,
+            wrap: |property, item| FilterItem::formula(property, FormulaFilterItem::number(item)),
+        }
+    }
+
+    pub fn text(self) -> TextCondition {
+        TextCondition {
+            property: self.property,
+            wrap: |property, item| FilterItem::formula(property, FormulaFilterItem::string(item)),
+        }
+    }
+}
+
+pub struct MultiSelectCondition {
+    property: String,
+}
+
+impl MultiSelectCondition {
+    pub fn contains(self, value: impl Into<String>) -> FilterItem {
+        FilterItem::multi_select(self.property, MultiSelectFilterItem::contains(value.into()))
+    }
+
+    pub fn does_not_contain(self, value: impl Into<String>) -> FilterItem {
+        FilterItem::multi_select(
+            self.property,
+            MultiSelectFilterItem::does_not_contain(value.into()),
+        )
+    }
+
+    pub fn is_empty(self) -> FilterItem {
+        FilterItem::multi_select(self.property, MultiSelectFilterItem::is_empty())
+    }
+
+    pub fn is_not_empty(self) -> FilterItem {
+        FilterItem::multi_select(self.property, MultiSelectFilterItem::is_not_empty())
+    }
+}
+
+pub struct NumberCondition {
+    property: String,
+    wrap: fn(String, NumberFilterItem) -> FilterItem,
+}
+
+impl NumberCondition {
+    fn build(self, item: NumberFilterItem) -> FilterItem {
+        (self.wrap)(self.property, item)
+    }
+
+    pub fn eq(self, value: impl Into<FilterNumber>) -> FilterItem {
+        self.build(NumberFilterItem::equals(value))
+    }
+
+    pub fn ne(self, value: impl Into<FilterNumber>) -> FilterItem {
+        self.build(NumberFilterItem::does_not_equal(value))
+    }
+
+    pub fn gt(self, value: impl Into<FilterNumber>) -> FilterItem {
+        self.build(NumberFilterItem::greater_than(value))
+    }
+
+    pub fn lt(self, value: impl Into<FilterNumber>) -> FilterItem {
+        self.build(NumberFilterItem::less_than(value))
+    }
+
+    pub fn gte(self, value: impl Into<FilterNumber>) -> FilterItem {
+        self.build(NumberFilterItem::greater_than_or_equal_to(value))
+    }
+
+    pub fn lte(self, value: impl Into<FilterNumber>) -> FilterItem {
+        self.build(NumberFilterItem::less_than_or_equal_to(value))
+    }
+
+    pub fn is_empty(self) -> FilterItem {
+        self.build(NumberFilterItem::is_empty())
+    }
+
+    pub fn is_not_empty(self) -> FilterItem {
+        self.build(NumberFilterItem::is_not_empty())
+    }
+}
+
+pub struct PeopleCondition {
+    property: String,
+    wrap: fn(String, PeopleFilterItem) -> FilterItem,
+}
+
+impl PeopleCondition {
+    /// `user_id` is the UUID of a Notion user
+    pub fn contains(self, user_id: impl Into<String>) -> FilterItem {
+        (self.wrap)(self.property, PeopleFilterItem::contains(user_id.into()))
+    }
+
+    pub fn does_not_contain(self, user_id: impl Into<String>) -> FilterItem {
+        (self.wrap)(
+            self.property,
+            PeopleFilterItem::does_not_contain(user_id.into()),
+        )
+    }
+
+    pub fn is_empty(self) -> FilterItem {
+        (self.wrap)(self.property, PeopleFilterItem::is_empty())
+    }
+
+    pub fn is_not_empty(self) -> FilterItem {
+        (self.wrap)(self.property, PeopleFilterItem::is_not_empty())
+    }
+}
+
+pub struct RelationCondition {
+    property: String,
+}
+
+impl RelationCondition {
+    /// `page_id` is the ID of a related page
+    pub fn contains(self, page_id: impl Into<String>) -> FilterItem {
+        FilterItem::relation(self.property, RelationFilterItem::contains(page_id.into()))
+    }
+
+    pub fn does_not_contain(self, page_id: impl Into<String>) -> FilterItem {
+        FilterItem::relation(
+            self.property,
+            RelationFilterItem::does_not_contain(page_id.into()),
+        )
+    }
+
+    pub fn is_empty(self) -> FilterItem {
+        FilterItem::relation(self.property, RelationFilterItem::is_empty())
+    }
+
+    pub fn is_not_empty(self) -> FilterItem {
+        FilterItem::relation(self.property, RelationFilterItem::is_not_empty())
+    }
+}
+
+/// A rollup property. `any`, `every` and `none` take a condition on the rolled up values, whose
+/// property name is ignored, e.g. `prop("Tasks").rollup().any(prop("").status().eq("Done"))`.
+pub struct RollupCondition {
+    property: String,
+}
+
+impl RollupCondition {
+    pub fn any(self, item: FilterItem) -> FilterItem {
+        FilterItem::rollup(self.property, RollupFilterItem::any(item))
+    }
+
+    pub fn every(self, item: FilterItem) -> FilterItem {
+        FilterItem::rollup(self.property, RollupFilterItem::every(item))
+    }
+
+    pub fn none(self, item: FilterItem) -> FilterItem {
+        FilterItem::rollup(self.property, RollupFilterItem::none(item))
+    }
+
+    pub fn number(self) -> NumberCondition {
+        NumberCondition {
+            property: self.property,
+            wrap: |property, item| FilterItem::rollup(property, RollupFilterItem::number(item)),
+        }
+    }
+
+    pub fn date(self) -> DateCondition {
+        DateCondition {
+            property: self.property,
+            wrap: |property, item| FilterItem::rollup(property, RollupFilterItem::date(item)),
+        }
+    }
+}
+
+pub struct TextCondition {
+    property: String,
+    wrap: fn(String, RichTextFilterItem) -> FilterItem,
+}
+
+impl TextCondition {
+    fn build(self, item: RichTextFilterItem) -> FilterItem {
+        (self.wrap)(self.property, item)
+    }
+
+    pub fn eq(self, value: impl Into<String>) -> FilterItem {
+        self.build(RichTextFilterItem::equals(value.into()))
+    }
+
+    pub fn ne(self, value: impl Into<String>) -> FilterItem {
+        self.build(RichTextFilterItem::does_not_equal(value.into()))
+    }
+
+    pub fn contains(self, value: impl Into<String>) -> FilterItem {
+        self.build(RichTextFilterItem::contains(value.into()))
+    }
+
+    pub fn does_not_contain(self, value: impl Into<String>) -> FilterItem {
+        self.build(RichTextFilterItem::does_not_contain(value.into()))
+    }
+
+    pub fn starts_with(self, value: impl Into<String>) -> FilterItem {
+        self.build(RichTextFilterItem::starts_with(value.into()))
+    }
+
+    pub fn ends_with(self, value: impl Into<String>) -> FilterItem {
+        self.build(RichTextFilterItem::ends_with(value.into()))
+    }
+
+    pub fn is_empty(self) -> FilterItem {
+        self.build(RichTextFilterItem::is_empty())
+    }
+
+    pub fn is_not_empty(self) -> FilterItem {
+        self.build(RichTextFilterItem::is_not_empty())
+    }
+}
+
+pub struct SelectCondition {
+    property: String,
+}
+
+impl SelectCondition {
+    pub fn eq(self, value: impl Into<String>) -> FilterItem {
+        FilterItem::select(self.property, SelectFilterItem::equals(value.into()))
+    }
+
+    pub fn ne(self, value: impl Into<String>) -> FilterItem {
+        FilterItem::select(
+            self.property,
+            SelectFilterItem::does_not_equals(value.into()),
+        )
+    }
+
+    pub fn is_empty(self) -> FilterItem {
+        FilterItem::select(self.property, SelectFilterItem::is_empty())
+    }
+
+    pub fn is_not_empty(self) -> FilterItem {
+        FilterItem::select(self.property, SelectFilterItem::is_not_empty())
+    }
+}
+
+pub struct StatusCondition {
+    property: String,
+}
+
+impl StatusCondition {
+    pub fn eq(self, value: impl Into<String>) -> FilterItem {
+        FilterItem::status(self.property, StatusFilterItem::equals(value.into()))
+    }
+
+    pub fn ne(self, value: impl Into<String>) -> FilterItem {
+        FilterItem::status(
+            self.property,
+            StatusFilterItem::does_not_equals(value.into()),
+        )
+    }
+
+    pub fn is_empty(self) -> FilterItem {
+        FilterItem::status(self.property, StatusFilterItem::is_empty())
+    }
+
+    pub fn is_not_empty(self) -> FilterItem {
+        FilterItem::status(self.property, StatusFilterItem::is_not_empty())
+    }
+}
+
+pub struct UniqueIdCondition {
+    property: String,
+    wrap: fn(String, IdFilterItem) -> FilterItem,
+}
+
+impl UniqueIdCondition {
+    pub fn eq(self, value: u128) -> FilterItem {
+        (self.wrap)(self.property, IdFilterItem::equals(value))
+    }
+
+    pub fn ne(self, value: u128) -> FilterItem {
+        (self.wrap)(self.property, IdFilterItem::does_not_equal(value))
+    }
+
+    pub fn gt(self, value: u128) -> FilterItem {
+        (self.wrap)(self.property, IdFilterItem::greater_than(value))
+    }
+
+    pub fn lt(self, value: u128) -> FilterItem {
+        (self.wrap)(self.property, IdFilterItem::less_than(value))
+    }
+
+    pub fn gte(self, value: u128) -> FilterItem {
+        (self.wrap)(self.property, IdFilterItem::greater_than_or_equal_to(value))
+    }
+
+    pub fn lte(self, value: u128) -> FilterItem {
+        (self.wrap)(self.property, IdFilterItem::less_than_or_equal_to(value))
+    }
+}
+
+pub struct VerificationCondition {
+    property: String,
+}
+
+impl VerificationCondition {
+    pub fn verified(self) -> FilterItem {
+        FilterItem::verification(self.property, VerificationFilterItem::verified())
+    }
+
+    pub fn expired(self) -> FilterItem {
+        FilterItem::verification(self.property, VerificationFilterItem::expired())
+    }
+
+    pub fn none(self) -> FilterItem {
+        FilterItem::verification(self.property, VerificationFilterItem::none())
+    }
+}
+
+impl FilterItem {
+    /// Conditions of `self` when it is nothing but an `and` group, otherwise `self` alone.
+    /// Empty items are dropped: the API rejects them.
+    fn into_and_items(mut self) -> Vec<FilterItem> {
+        let and = self.and.take();
+        match and {
+            Some(items) if self.is_empty() => items.into_iter().filter(|i| !i.is_empty()).collect(),
+            None if self.is_empty() => Vec::new(),
+            _ => {
+                self.and = and;
+                vec![self]
+            }
+        }
+    }
+
+    /// Conditions of `self` when it is nothing but an `or` group, otherwise `self` alone.
+    /// Empty items are dropped: the API rejects them.
+    fn into_or_items(mut self) -> Vec<FilterItem> {
+        let or = self.or.take();
+        match or {
+            Some(items) if self.is_empty() => items.into_iter().filter(|i| !i.is_empty()).collect(),
+            None if self.is_empty() => Vec::new(),
+            _ => {
+                self.or = or;
+                vec![self]
+            }
+        }
+    }
+
+    /// `items` combined with `group`, or the single item itself
+    fn group(mut items: Vec<FilterItem>, group: fn(Vec<FilterItem>) -> FilterItem) -> FilterItem {
+        match items.len() {
+            0 => FilterItem::default(),
+            1 => items.remove(0),
+            _ => group(items),
+        }
+    }
+}
+
+impl BitAnd for FilterItem {
+    type Output = FilterItem;
+
+    fn bitand(self, rhs: FilterItem) -> FilterItem {
+        let mut items = self.into_and_items();
+        items.extend(rhs.into_and_items());
+        FilterItem::group(items, FilterItem::and)
+    }
+}
+
+impl BitOr for FilterItem {
+    type Output = FilterItem;
+
+    fn bitor(self, rhs: FilterItem) -> FilterItem {
+        let mut items = self.into_or_items();
+        items.extend(rhs.into_or_items());
+        FilterItem::group(items, FilterItem::or)
+    }
+}
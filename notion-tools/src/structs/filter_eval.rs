@@ -0,0 +1,627 @@
+//! Local evaluation of [`FilterItem`] trees against [`Page`] values.
+//!
+//! The rules follow the Notion API:
+//! - `contains`, `starts_with` and `ends_with` on text are case-insensitive, `equals` is exact.
+//! - Negative conditions (`does_not_equal`, `does_not_contain`, ...) match empty values.
+//! - Date comparisons use the start of a date range and compare whole days when either side has
+//!   no time. Date-times without an offset are taken as UTC.
+//! - `this_week` runs from Monday to Sunday; the other relative conditions include today.
+//! - A condition on a property the page does not have never matches.
+//!
+use crate::structs::common::{DateValue, Formula, SelectOption};
+use crate::structs::page::{Page, PageProperty, Rollup};
+use crate::structs::query_filter::*;
+use std::cmp::Ordering;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+
+impl FilterItem {
+    /// Evaluate the filter against `page` without calling the API.
+    /// Relative dates such as `past_week` are resolved against the current date (UTC).
+    pub fn matches(&self, page: &Page) -> bool {
+        let today = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs() as i64 / 86_400);
+        self.evaluate(page, Some(today))
+    }
+
+    /// Same as [`FilterItem::matches`], with relative dates resolved against `today` (`YYYY-MM-DD`)
+    pub fn matches_on(&self, page: &Page, today: impl Into<DateValue>) -> bool {
+        let today = parse_date(today.into().as_str()).map(|moment| moment.day());
+        self.evaluate(page, today)
+    }
+
+    fn evaluate(&self, page: &Page, today: Option<i64>) -> bool {
+        let and = self
+            .and
+            .iter()
+            .flatten()
+            .all(|item| item.evaluate(page, today));
+        let or = self.or.as_ref().is_none_or(|items| {
+            items.is_empty() || items.iter().any(|item| item.evaluate(page, today))
+        });
+        let timestamp = self.timestamp.as_ref().is_none_or(|timestamp| {
+            let created_time = timestamp
+                .created_time
+                .as_ref()
+                .is_none_or(|item| item.evaluate(Some(&page.created_time), today));
+            let last_edited_time = timestamp
+                .last_edited_time
+                .as_ref()
+                .is_none_or(|item| item.evaluate(Some(&page.last_edited_time), today));
+            created_time && last_edited_time
+        });
+        if !(and && or && timestamp) {
+            return false;
+        }
+
+        // timestamp conditions apply to the page itself
+        if self
+            .condition_names()
+            .iter()
+            .all(|name| *name == "timestamp")
+        {
+            return true;
+        }
+        page.properties
+            .get(&self.property)
+            .or_else(|| {
+                page.properties
+                    .values()
+                    .find(|property| property.id == self.property)
+            })
+            .is_some_and(|property| self.evaluate_property(property, today))
+    }
+
+    /// Evaluate the property conditions of this item against a single property value
+    fn evaluate_property(&self, property: &PageProperty, today: Option<i64>) -> bool {
+        let text = text_value(property);
+        let users = user_ids(property);
+        self.checkbox
+            .as_ref()
+            .is_none_or(|item| item.evaluate(property.checkbox))
+            && self
+                .date
+                .as_ref()
+                .is_none_or(|item| item.evaluate(date_value(property), today))
+            && self
+                .files
+                .as_ref()
+                .is_none_or(|item| item.evaluate(property.files.as_ref().map_or(0, Vec::len)))
+            && self
+                .formula
+                .as_ref()
+                .is_none_or(|item| item.evaluate(property.formula.as_ref(), today))
+            && self
+                .multi_select
+                .as_ref()
+                .is_none_or(|item| item.evaluate(property.multi_select.as_deref().unwrap_or(&[])))
+            && self
+                .number
+                .as_ref()
+                .is_none_or(|item| item.evaluate(property.number))
+            && self
+                .people
+                .as_ref()
+                .is_none_or(|item| item.evaluate(&users))
+            && self.created_by.as_ref().is_none_or(|item| {
+                item.evaluate(
+                    &property
+                        .created_by
+                        .iter()
+                        .map(|user| user.id.as_str())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            && self.last_edited_by.as_ref().is_none_or(|item| {
+                item.evaluate(
+                    &property
+                        .last_edited_by
+                        .iter()
+                        .map(|user| user.id.as_str())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            && self.relation.as_ref().is_none_or(|item| {
+                item.evaluate(
+                    &property
+                        .relation
+                        .iter()
+                        .flatten()
+                        .map(|relation| relation.id.as_str())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            && self
+                .rich_text
+                .as_ref()
+                .is_none_or(|item| item.evaluate(&text))
+            && self
+                .select
+                .as_ref()
+                .is_none_or(|item| item.evaluate(option_name(&property.select)))
+            && self
+                .status
+                .as_ref()
+                .is_none_or(|item| item.evaluate(option_name(&property.status)))
+            && [&self.id, &self.unique_id]
+                .into_iter()
+                .flatten()
+                .all(|item| {
+                    item.evaluate(
+                        property
+                            .unique_id
+                            .as_ref()
+                            .map(|unique_id| unique_id.number),
+                    )
+                })
+            && self
+                .rollup
+                .as_ref()
+                .is_none_or(|item| item.evaluate(property.rollup.as_ref(), today))
+            && self.verification.as_ref().is_none_or(|item| {
+                item.evaluate(property.verification.as_ref().map(|v| v.state.as_str()))
+            })
+    }
+}
+
+impl QueryFilter {
+    /// Evaluate the filter against `page` without calling the API. See [`FilterItem::matches`].
+    pub fn matches(&self, page: &Page) -> bool {
+        self.filter.matches(page)
+    }
+}
+
+/// `is_empty`/`is_not_empty` flags
+fn emptiness_matches(is_empty: Option<bool>, is_not_empty: Option<bool>, empty: bool) -> bool {
+    is_empty.is_none_or(|flag| flag == empty) && is_not_empty.is_none_or(|flag| flag != empty)
+}
+
+/// Plain text of title, rich text, URL, email and phone number properties
+fn text_value(property: &PageProperty) -> String {
+    if let Some(rich_text) = property.title.as_ref().or(property.rich_text.as_ref()) {
+        rich_text
+            .iter()
+            .map(|text| text.plain_text.as_str())
+            .collect()
+    } else if let Some(url) = &property.url {
+        url.to_string()
+    } else if let Some(email) = &property.email {
+        email.email.to_string()
+    } else if let Some(phone_number) = &property.phone_number {
+        phone_number.phone_number.to_string()
+    } else {
+        String::new()
+    }
+}
+
+/// IDs of the users of people, created_by and last_edited_by properties
+fn user_ids(property: &PageProperty) -> Vec<&str> {
+    property
+        .people
+        .iter()
+        .flatten()
+        .chain(property.created_by.iter())
+        .chain(property.last_edited_by.iter())
+        .map(|user| user.id.as_str())
+        .collect()
+}
+
+/// Start of date properties, or the value of created_time and last_edited_time properties
+fn date_value(property: &PageProperty) -> Option<&String> {
+    property
+        .date
+        .as_ref()
+        .map(|date| &date.start)
+        .or(property.created_time.as_ref())
+        .or(property.last_edited_time.as_ref())
+}
+
+fn option_name(option: &Option<SelectOption>) -> &str {
+    option.as_ref().map_or("", |option| option.name.as_str())
+}
+
+/// Notion IDs are compared without dashes and case-insensitively
+fn same_id(a: &str, b: &str) -> bool {
+    a.replace('-', "").eq_ignore_ascii_case(&b.replace('-', ""))
+}
+
+impl CheckboxFilterItem {
+    fn evaluate(&self, value: Option<bool>) -> bool {
+        let value = value.unwrap_or(false);
+        self.equals.is_none_or(|equals| value == equals)
+            && self.does_not_equal.is_none_or(|other| value != other)
+    }
+}
+
+impl DateFilterItem {
+    fn evaluate(&self, value: Option<&String>, today: Option<i64>) -> bool {
+        let value = value.and_then(|value| parse_date(value));
+        if !emptiness_matches(self.is_empty, self.is_not_empty, value.is_none()) {
+            return false;
+        }
+        let compare = |filter: &Option<String>, accept: fn(Ordering) -> bool| {
+            filter
+                .as_ref()
+                .is_none_or(|filter| match (value, parse_date(filter)) {
+                    (Some(value), Some(filter)) => accept(value.compare(&filter)),
+                    _ => false,
+                })
+        };
+        let relative = |filter: &Option<_>, range: fn(i64) -> (i64, i64)| {
+            filter.as_ref().is_none_or(|_| match (value, today) {
+                (Some(value), Some(today)) => {
+                    let (from, to) = range(today);
+                    (from..=to).contains(&value.day())
+                }
+                _ => false,
+            })
+        };
+        compare(&self.equals, Ordering::is_eq)
+            && compare(&self.before, Ordering::is_lt)
+            && compare(&self.after, Ordering::is_gt)
+            && compare(&self.on_or_before, Ordering::is_le)
+            && compare(&self.on_or_after, Ordering::is_ge)
+            && relative(&self.past_week, |today| (today - 7, today))
+            && relative(&self.past_month, |today| (add_months(today, -1), today))
+            && relative(&self.past_year, |today| (add_months(today, -12), today))
+            && relative(&self.next_week, |today| (today, today + 7))
+            && relative(&self.next_month, |today| (today, add_months(today, 1)))
+            && relative(&self.next_year, |today| (today, add_months(today, 12)))
+            && relative(&self.this_week, |today| {
+                let monday = today - (today + 3).rem_euclid(7);
+                (monday, monday + 6)
+            })
+    }
+}
+
+impl FilesFilterItem {
+    fn evaluate(&self, count: usize) -> bool {
+        emptiness_matches(self.is_empty, self.is_not_empty, count == 0)
+    }
+}
+
+impl FormulaFilterItem {
+    fn evaluate(&self, formula: Option<&Formula>, today: Option<i64>) -> bool {
+        let type_name = formula.map_or("", |formula| formula.type_name.as_str());
+        let checkbox = (type_name == "boolean").then(|| formula.map(|formula| formula.boolean));
+        let date = formula
+            .filter(|formula| type_name == "date" && !formula.date.is_empty())
+            .map(|formula| &formula.date);
+        let number = formula
+            .filter(|_| type_name == "number")
+            .map(|formula| formula.number);
+        let string = formula
+            .filter(|_| type_name == "string")
+            .map_or("", |formula| formula.string.as_str());
+        self.checkbox
+            .as_ref()
+            .is_none_or(|item| item.evaluate(checkbox.flatten()))
+            && self
+                .date
+                .as_ref()
+                .is_none_or(|item| item.evaluate(date, today))
+            && self
+                .number
+                .as_ref()
+                .is_none_or(|item| item.evaluate(number))
+            && self
+                .string
+                .as_ref()
+                .is_none_or(|item| item.evaluate(string))
+    }
+}
+
+impl MultiSelectFilterItem {
+    fn evaluate(&self, options: &[SelectOption]) -> bool {
+        let contains = |name: &String| options.iter().any(|option| &option.name == name);
+        emptiness_matches(self.is_empty, self.is_not_empty, options.is_empty())
+            && self.contains.as_ref().is_none_or(contains)
+            && self
+                .does_not_contain
+                .as_ref()
+                .is_none_or(|name| !contains(name))
+    }
+}
+
+impl NumberFilterItem {
+    fn evaluate(&self, value: Option<f64>) -> bool {
+        let compare = |filter: &Option<FilterNumber>, accept: fn(Ordering) -> bool| {
+            filter.as_ref().is_none_or(|filter| {
+                value
+                    .and_then(|value| value.partial_cmp(&filter.as_f64()))
+                    .is_some_and(accept)
+            })
+        };
+        emptiness_matches(self.is_empty, self.is_not_empty, value.is_none())
+            && compare(&self.equals, Ordering::is_eq)
+            && self
+                .does_not_equal
+                .as_ref()
+                .is_none_or(|filter| value != Some(filter.as_f64()))
+            && compare(&self.greater_than, Ordering::is_gt)
+            && compare(&self.less_than, Ordering::is_lt)
+            && compare(&self.greater_than_or_equal_to, Ordering::is_ge)
+            && compare(&self.less_than_or_equal_to, Ordering::is_le)
+    }
+}
+
+impl PeopleFilterItem {
+    fn evaluate(&self, user_ids: &[&str]) -> bool {
+        let contains = |id: &String| user_ids.iter().any(|user_id| same_id(user_id, id));
+        emptiness_matches(self.is_empty, self.is_not_empty, user_ids.is_empty())
+            && self.contains.as_ref().is_none_or(contains)
+            && self
+                .does_not_contain
+                .as_ref()
+                .is_none_or(|id| !contains(id))
+    }
+}
+
+impl RelationFilterItem {
+    fn evaluate(&self, page_ids: &[&str]) -> bool {
+        let contains = |id: &String| page_ids.iter().any(|page_id| same_id(page_id, id));
+        emptiness_matches(self.is_empty, self.is_not_empty, page_ids.is_empty())
+            && self.contains.as_ref().is_none_or(contains)
+            && self
+                .does_not_contain
+                .as_ref()
+                .is_none_or(|id| !contains(id))
+    }
+}
+
+impl RichTextFilterItem {
+    fn evaluate(&self, value: &str) -> bool {
+        let lowercase = value.to_lowercase();
+        emptiness_matches(self.is_empty, self.is_not_empty, value.is_empty())
+            && self.equals.as_ref().is_none_or(|filter| value == filter)
+            && self
+                .does_not_equal
+                .as_ref()
+                .is_none_or(|filter| value != filter)
+            && self
+                .contains
+                .as_ref()
+                .is_none_or(|filter| lowercase.contains(&filter.to_lowercase()))
+            && self
+                .does_not_contain
+                .as_ref()
+                .is_none_or(|filter| !lowercase.contains(&filter.to_lowercase()))
+            && self
+                .starts_with
+                .as_ref()
+                .is_none_or(|filter| lowercase.starts_with(&filter.to_lowercase()))
+            && self
+                .ends_with
+                .as_ref()
+                .is_none_or(|filter| lowercase.ends_with(&filter.to_lowercase()))
+    }
+}
+
+impl SelectFilterItem {
+    fn evaluate(&self, name: &str) -> bool {
+        emptiness_matches(self.is_empty, self.is_not_empty, name.is_empty())
+            && self.equals.as_ref().is_none_or(|filter| name == filter)
+            && self
+                .does_not_equals
+                .as_ref()
+                .is_none_or(|filter| name != filter)
+    }
+}
+
+impl StatusFilterItem {
+    fn evaluate(&self, name: &str) -> bool {
+        emptiness_matches(self.is_empty, self.is_not_empty, name.is_empty())
+            && self.equals.as_ref().is_none_or(|filter| name == filter)
+            && self
+                .does_not_equals
+                .as_ref()
+                .is_none_or(|filter| name != filter)
+    }
+}
+
+impl IdFilterItem {
+    fn evaluate(&self, value: Option<u128>) -> bool {
+        let compare = |filter: Option<u128>, accept: fn(Ordering) -> bool| {
+            filter.is_none_or(|filter| value.is_some_and(|value| accept(value.cmp(&filter))))
+        };
+        compare(self.equals, Ordering::is_eq)
+            && self
+                .does_not_equal
+                .is_none_or(|filter| value != Some(filter))
+            && compare(self.greater_than, Ordering::is_gt)
+            && compare(self.less_than, Ordering::is_lt)
+            && compare(self.greater_than_or_equal_to, Ordering::is_ge)
+            && compare(self.less_than_or_equal_to, Ordering::is_le)
+    }
+}
+
+impl RollupFilterItem {
+    fn evaluate(&self, rollup: Option<&Rollup>, today: Option<i64>) -> bool {
+        let values = rollup
+            .and_then(|rollup| rollup.array.as_deref())
+            .unwrap_or(&[]);
+        let value_matches =
+            |item: &FilterItem, value: &PageProperty| item.evaluate_property(value, today);
+        self.any
+            .as_ref()
+            .is_none_or(|item| values.iter().any(|value| value_matches(item, value)))
+            && self
+                .every
+                .as_ref()
+                .is_none_or(|item| values.iter().all(|value| value_matches(item, value)))
+            && self
+                .none
+                .as_ref()
+                .is_none_or(|item| !values.iter().any(|value| value_matches(item, value)))
+            && self
+                .number
+                .as_ref()
+                .is_none_or(|item| item.evaluate(rollup.and_then(|rollup| rollup.number)))
+            && self.date.as_ref().is_none_or(|item| {
+                item.evaluate(
+                    rollup
+                        .and_then(|rollup| rollup.date.as_ref())
+                        .map(|date| &date.start),
+                    today,
+                )
+            })
+    }
+}
+
+impl VerificationFilterItem {
+    fn evaluate(&self, state: Option<&str>) -> bool {
+        // pages that were never verified have the state `unverified`, filtered as `none`
+        let state = match state.unwrap_or("unverified") {
+            "unverified" => "none",
+            state => state,
+        };
+        self.status.as_ref().is_none_or(|status| status == state)
+    }
+}
+
+/// A point in time parsed from an ISO 8601 date or date-time
+#[derive(Debug, Clone, Copy)]
+struct Moment {
+    /// Milliseconds since the Unix epoch, in UTC
+    millis: i64,
+    /// `false` for dates without a time
+    has_time: bool,
+}
+
+impl Moment {
+    /// Days since the Unix epoch
+    fn day(&self) -> i64 {
+        self.millis.div_euclid(MILLIS_PER_DAY)
+    }
+
+    fn compare(&self, other: &Moment) -> Ordering {
+        if self.has_time && other.has_time {
+            self.millis.cmp(&other.millis)
+        } else {
+            self.day().cmp(&other.day())
+        }
+    }
+}
+
+/// Parse `YYYY-MM-DD` and `YYYY-MM-DDTHH:MM[:SS[.fff]][Z|±HH:MM]`
+fn parse_date(value: &str) -> Option<Moment> {
+    let number = |range: std::ops::Range<usize>| -> Option<i64> {
+        let digits = value.get(range)?;
+        digits
+            .bytes()
+            .all(|b| b.is_ascii_digit())
+            .then(|| digits.parse().ok())?
+    };
+    let separator = |index: usize, expected: u8| value.as_bytes().get(index) == Some(&expected);
+
+    if !(separator(4, b'-') && separator(7, b'-')) {
+        return None;
+    }
+    let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+    if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+        return None;
+    }
+    let days = days_from_civil(year, month, day);
+    if value.len() == 10 {
+        return Some(Moment {
+            millis: days * MILLIS_PER_DAY,
+            has_time: false,
+        });
+    }
+
+    if !(separator(10, b'T') || separator(10, b't') || separator(10, b' ')) || !separator(13, b':')
+    {
+        return None;
+    }
+    let (hour, minute) = (number(11..13)?, number(14..16)?);
+    let mut rest = &value[16..];
+    let mut second = 0;
+    if let Some(seconds) = rest.strip_prefix(':') {
+        second = seconds.get(0..2)?.parse().ok()?;
+        rest = &seconds[2..];
+    }
+    let mut millis = 0;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+        let padded = format!("{:0<3}", &fraction[..digits.min(3)]);
+        millis = padded.parse().ok()?;
+        rest = &fraction[digits..];
+    }
+    let offset_minutes = match rest {
+        "" | "Z" | "z" => 0,
+        _ => {
+            let sign = match rest.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let offset = rest[1..].replace(':', "");
+            if offset.len() != 4 {
+                return None;
+            }
+            let (hours, minutes) = (offset.get(0..2)?, offset.get(2..4)?);
+            sign * (hours.parse::<i64>().ok()? * 60 + minutes.parse::<i64>().ok()?)
+        }
+    };
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    Some(Moment {
+        millis: days * MILLIS_PER_DAY
+            + ((hour * 60 + minute - offset_minutes) * 60 + second) * 1000
+            + millis,
+        has_time: true,
+    })
+}
+
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Proleptic Gregorian date of a number of days since 1970-01-01
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Move `days` by whole months, keeping the day of the month when it exists
+fn add_months(days: i64, months: i64) -> i64 {
+    let (year, month, day) = civil_from_days(days);
+    let month_index = year * 12 + (month - 1) + months;
+    let (year, month) = (month_index.div_euclid(12), month_index.rem_euclid(12) + 1);
+    days_from_civil(year, month, day.min(days_in_month(year, month)))
+}
@@ -1,6 +1,8 @@
 pub mod block;
 pub mod common;
 pub mod database;
+pub mod filter_dsl;
+mod filter_eval;
 pub mod page;
 pub mod query_filter;
 
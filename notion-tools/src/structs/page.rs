@@ -73,6 +73,8 @@ pub struct PageProperty {
     pub url: Option<String>,
     #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
     pub unique_id: Option<UniqueId>,
+    #[serde(default = "Option::default", skip_serializing)]
+    pub verification: Option<Verification>,
 }
 
 impl PageProperty {
@@ -83,15 +85,41 @@ impl PageProperty {
         return prop;
     }
 
-    pub fn date(value: String) -> Self {
+    pub fn date(value: impl Into<DateValue>) -> Self {
         let mut prop = PageProperty::default();
         prop.type_name = "date".to_string();
-        prop.date = Some(Date {
-            start: value.to_string(),
-        });
+        prop.date = Some(Date::new(value));
         return prop;
     }
 
+    pub fn date_range(start: impl Into<DateValue>, end: impl Into<DateValue>) -> Self {
+        PageProperty {
+            type_name: "date".to_string(),
+            date: Some(Date::range(start, end)),
+            ..Default::default()
+        }
+    }
+
+    pub fn zoned_date(value: impl Into<DateValue>, time_zone: impl Into<String>) -> Self {
+        PageProperty {
+            type_name: "date".to_string(),
+            date: Some(Date::zoned(value, time_zone)),
+            ..Default::default()
+        }
+    }
+
+    pub fn zoned_date_range(
+        start: impl Into<DateValue>,
+        end: impl Into<DateValue>,
+        time_zone: impl Into<String>,
+    ) -> Self {
+        PageProperty {
+            type_name: "date".to_string(),
+            date: Some(Date::range(start, end).with_time_zone(time_zone)),
+            ..Default::default()
+        }
+    }
+
     pub fn email(value: String) -> Self {
         let mut prop = PageProperty::default();
         prop.type_name = "email".to_string();
@@ -181,7 +209,7 @@ impl PageProperty {
             }
             "date" => {
                 if let Some(value) = &self.date {
-                    return value.start.to_string();
+                    return value.get_value();
                 }
             }
             "email" => {
@@ -328,6 +356,32 @@ impl Page {
         page.properties = properties;
         return page;
     }
+
+    /// `created_time` as a parsed date-time
+    #[cfg(feature = "chrono")]
+    pub fn created_datetime(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        chrono::DateTime::parse_from_rfc3339(&self.created_time)
+            .ok()
+            .map(|datetime| datetime.to_utc())
+    }
+
+    /// `last_edited_time` as a parsed date-time
+    #[cfg(feature = "chrono")]
+    pub fn last_edited_datetime(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        chrono::DateTime::parse_from_rfc3339(&self.last_edited_time)
+            .ok()
+            .map(|datetime| datetime.to_utc())
+    }
+
+    /// Reject date properties that are not valid ISO 8601
+    #[cfg(feature = "chrono")]
+    pub fn validate_dates(&self) -> crate::error::Result<()> {
+        self.properties
+            .values()
+            .filter_map(|prop| prop.date.as_ref())
+            .flat_map(|date| std::iter::once(&date.start).chain(date.end.iter()))
+            .try_for_each(|value| validate_date(value))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
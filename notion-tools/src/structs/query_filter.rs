@@ -32,43 +32,223 @@
 //! # }
 //! ```
 //!
-//! ### Pagenation
+//! The [`filter_dsl`](crate::structs::filter_dsl) module builds the same tree with less typing:
+//!
+//! ```rust
+//! # fn main() {
+//! # use notion_tools::structs::filter_dsl::prop;
+//! # use notion_tools::structs::query_filter::*;
+//! let mut query_filter = QueryFilter::new();
+//! query_filter.args(
+//!     prop("Status").status().eq("Active")
+//!         & prop("Name").text().contains("Zack")
+//!         & (prop("Age").number().gt(18) | prop("Address").text().contains("New York")),
+//! );
+//! let filter = query_filter.build();
+//! # }
+//! ```
+//!
+//! ### Relative dates
+//! Date filters support relative conditions such as `past_week` or `next_month`, also for
+//! timestamps and date formulas.
+//!
+//! ```rust
+//! # fn main() {
+//! # use notion_tools::structs::query_filter::*;
+//! let mut query_filter = QueryFilter::new();
+//! query_filter.and(vec![
+//!    FilterItem::date(String::from("Due"), DateFilterItem::past_week()),
+//!    FilterItem::formula(String::from("Next Review"), FormulaFilterItem::date(DateFilterItem::next_month())),
+//! ]);
+//! let filter = query_filter.build();
+//! # }
+//! ```
+//!
+//! ### Rollups, people and IDs
+//! Rollup filters apply a condition to `any`, `every` or `none` of the rolled up values.
+//!
+//! ```rust
+//! # fn main() {
+//! # use notion_tools::structs::query_filter::*;
+//! let mut query_filter = QueryFilter::new();
+//! query_filter.and(vec![
+//!    FilterItem::rollup(String::from("Tasks"), RollupFilterItem::every(
+//!        FilterItem::status(String::new(), StatusFilterItem::equals(String::from("Done"))),
+//!    )),
+//!    FilterItem::created_by(String::from("Created by"), PeopleFilterItem::is_not_empty()),
+//!    FilterItem::unique_id(String::from("ID"), IdFilterItem::less_than(100)),
+//! ]);
+//! let filter = query_filter.build();
+//! # }
+//! ```
+//!
+//! ### Sort the results
+//! Sorts are applied in the order they are added.
+//!
+//! ```rust
+//! # fn main() {
+//! # use notion_tools::structs::query_filter::*;
+//! let mut query_filter = QueryFilter::new();
+//! query_filter.args(FilterItem::status(String::from("Status"), StatusFilterItem::equals(String::from("ToDo"))));
+//! query_filter.sort(Sort::descending(String::from("Priority")));
+//! query_filter.sort(Sort::created_time(SortDirection::Descending));
+//! let filter = query_filter.build();
+//! # }
+//! ```
+//!
+//! ### Limit the size of the response
+//! `page_size` limits the number of pages per response, and `filter_properties` limits the
+//! properties returned for each page to the given property IDs.
 //!
 //! ```rust
+//! # fn main() {
+//! # use notion_tools::structs::query_filter::*;
+//! let mut query_filter = QueryFilter::new();
+//! query_filter.page_size(20);
+//! query_filter.filter_properties(vec![String::from("title"), String::from("%3AUPp")]);
+//! let filter = query_filter.build();
+//! # }
+//! ```
+//!
+//! ### Validate against the database schema
+//! [`QueryFilter::validate`] reports unknown properties, filter types that do not match the
+//! property type, too deeply nested `and`/`or` groups, invalid rollup `any`/`every`/`none`
+//! conditions and non-finite numbers before the query is sent.
+//!
+//! ```rust,no_run
+//! # use notion_tools::Notion;
+//! # use notion_tools::structs::filter_dsl::prop;
+//! # use notion_tools::structs::query_filter::QueryFilter;
+//! # #[tokio::main]
+//! # async fn main() -> notion_tools::error::Result<()> {
+//! let notion = Notion::from_env()?;
+//! let database_id = String::from("your_database_id");
+//! let database = notion.retrieve_a_database(database_id.clone()).await?;
+//!
+//! let mut filter = QueryFilter::new();
+//! filter.args(prop("Status").select().eq("Active"));
+//! if let Err(errors) = filter.validate(&database) {
+//!     for error in errors {
+//!         eprintln!("{}", error);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ### Text query language
+//! [`QueryFilter::parse`] reads filters written as text, e.g. in a command line or a config file,
+//! and the [`Display`](std::fmt::Display) impl of [`FilterItem`] writes them back for logging.
+//!
+//! A condition is `Property[:type] operator [value]`. Conditions are combined with `and`, `or`
+//! and parentheses; `and` binds tighter than `or`.
+//! - Property names and values with spaces or symbols are quoted: `"Due date"`, `"say \"hi\""`.
+//!   ISO 8601 dates and date-times can be written without quotes: `Due > 2024-01-01T10:00:00Z`.
+//! - Types: `checkbox`, `date`, `files`, `multi_select`, `number`, `people`, `created_by`,
+//!   `last_edited_by`, `relation`, `rich_text`, `select`, `status`, `unique_id`, `verification`,
+//!   `created_time`, `last_edited_time`, `rollup`, `rollup.number`, `rollup.date`,
+//!   `formula.checkbox`, `formula.date`, `formula.number` and `formula.string`.
+//! - Operators: `=`, `!=`, `>`, `>=`, `<`, `<=`, `contains`, `not contains`, `starts with`,
+//!   `ends with`, `has`, `not has`, `is empty`, `is not empty`, `in past_week` (and the other
+//!   relative dates), and `any`, `every`, `none` followed by a parenthesized condition for rollups.
+//!   Conditions inside a rollup start with the type and have no property:
+//!   `Tasks:rollup any (status = "Done")`.
+//! - Without a type, numbers imply `number`, `true`/`false` imply `checkbox`, `has` implies
+//!   `multi_select`, `contains` or `=`/`!=` with a text imply `rich_text`, and `in` or a
+//!   comparison with a text implies `date`. `is empty` has no default and needs a type.
+//!   [`QueryFilter::parse_with_schema`] takes the types from the database instead.
+//!
+//! ```rust
+//! # fn main() {
+//! # use notion_tools::structs::query_filter::*;
+//! let query_filter =
+//!     QueryFilter::parse(r#"Status = "ToDo" and (Priority >= 2 or Tags has "urgent")"#).unwrap();
+//! assert_eq!(
+//!     query_filter.filter.to_string(),
+//!     r#"Status:rich_text = "ToDo" and (Priority:number >= 2 or Tags:multi_select has "urgent")"#,
+//! );
+//! # }
+//! ```
+//!
+//! A plain `Status = "ToDo"` does not say whether `Status` is a status, a select or a text
+//! property, so it is read as `rich_text`. Write `Status:status` or use the database schema for
+//! the other types:
+//!
+//! ```rust
+//! # fn main() {
+//! # use notion_tools::structs::database::Database;
+//! # use notion_tools::structs::query_filter::*;
+//! # let database: Database = serde_json::from_str(r#"{"properties": {
+//! #     "Status": {"id": "s", "type": "status", "status": {"options": []}},
+//! #     "Priority": {"id": "p", "type": "number", "number": {}}
+//! # }}"#).unwrap();
+//! // `database` is the result of `Notion::retrieve_a_database`
+//! let query_filter =
+//!     QueryFilter::parse_with_schema(r#"Status = "ToDo" and Priority >= 2"#, &database).unwrap();
+//! assert_eq!(
+//!     query_filter.filter.to_string(),
+//!     r#"Status:status = "ToDo" and Priority:number >= 2"#,
+//! );
+//! # }
+//! ```
+//!
+//! ### Evaluate a filter locally
+//! [`FilterItem::matches`] and [`QueryFilter::matches`] apply a filter to pages you already have,
+//! e.g. a local cache, without calling the API.
+//!
+//! ```rust
+//! # fn main() {
+//! # use notion_tools::structs::filter_dsl::prop;
+//! # use notion_tools::structs::page::{Page, PageProperty};
+//! # use notion_tools::structs::query_filter::QueryFilter;
+//! # let mut page = Page::default();
+//! # let mut status = PageProperty::status(String::from("Active"));
+//! # status.type_name = String::from("status");
+//! # page.properties.insert(String::from("Status"), status);
+//! let mut query_filter = QueryFilter::new();
+//! query_filter.args(prop("Status").status().eq("Active") | prop("Due").date().past_week());
+//! assert!(query_filter.matches(&page));
+//! # }
+//! ```
+//!
+//! ### Pagenation
+//!
+//! [`Notion::query_database_stream`](crate::Notion::query_database_stream) follows `next_cursor`
+//! for you, and [`Notion::query_database_all`](crate::Notion::query_database_all) collects every page.
+//!
+//! ```rust,no_run
 //! # use notion_tools::Notion;
 //! # use notion_tools::structs::query_filter::{QueryFilter, StatusFilterItem, FilterItem};
+//! # use futures::TryStreamExt;
 //! # #[tokio::main]
-//! # async fn main() {
-//! let notion = Notion::new();
+//! # async fn main() -> notion_tools::error::Result<()> {
+//! let notion = Notion::from_env()?;
+//! let database_id = String::from("your_database_id");
 //! let mut filter = QueryFilter::new();
 //! filter.args(FilterItem::status(
 //!     String::from("Status"),
 //!     StatusFilterItem::is_not_empty(),
 //! ));
 //!
-//! let mut has_more = true;
-//!
-//! while has_more {
-//!     let response = notion.query_database(filter.clone()).await;
-//!
-//!     match response {
-//!         Ok(response) => {
-//!             has_more = response.has_more.unwrap_or(false);
-//!             filter.start_cursor = response.next_cursor.unwrap_or(String::new());
-//!             for result in response.results {
-//!                // Do something with the result
-//!             }
-//!         }
-//!         Err(e) => {
-//!             println!("{:?}", e);
-//!         }
-//!     }
-//!     # has_more = false;
+//! let mut pages = Box::pin(notion.query_database_stream(database_id.clone(), filter.clone()));
+//! while let Some(page) = pages.try_next().await? {
+//!     // Do something with the page
 //! }
+//!
+//! // or fetch everything at once
+//! let pages = notion.query_database_all(database_id, filter).await?;
+//! # Ok(())
 //! # }
 //! ```
 //!
+#[cfg(feature = "chrono")]
+use crate::structs::common::validate_date;
+use crate::structs::common::DateValue;
+use crate::structs::database::{Database, DatabaseProperty};
+use fxhash::FxHashMap;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct CheckboxFilterItem {
@@ -79,19 +259,32 @@ pub struct CheckboxFilterItem {
 }
 
 impl CheckboxFilterItem {
-    pub fn equals() -> Self {
+    /// Match checked boxes with `is(true)` and unchecked boxes with `is(false)`
+    pub fn is(value: bool) -> Self {
         CheckboxFilterItem {
-            equals: Some(true),
+            equals: Some(value),
             ..Default::default()
         }
     }
 
-    pub fn does_not_equal() -> Self {
+    pub fn is_not(value: bool) -> Self {
         CheckboxFilterItem {
-            does_not_equal: Some(true),
+            does_not_equal: Some(value),
             ..Default::default()
         }
     }
+
+    #[deprecated(note = "use `CheckboxFilterItem::is(true)`")]
+    pub fn equals() -> Self {
+        CheckboxFilterItem::is(true)
+    }
+
+    #[deprecated(
+        note = "use `CheckboxFilterItem::is(false)` or `CheckboxFilterItem::is_not(true)`"
+    )]
+    pub fn does_not_equal() -> Self {
+        CheckboxFilterItem::is_not(true)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -110,26 +303,40 @@ pub struct DateFilterItem {
     pub on_or_after: Option<String>,
     #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
     pub on_or_before: Option<String>,
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub past_week: Option<FxHashMap<String, String>>,
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub past_month: Option<FxHashMap<String, String>>,
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub past_year: Option<FxHashMap<String, String>>,
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub next_week: Option<FxHashMap<String, String>>,
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub next_month: Option<FxHashMap<String, String>>,
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub next_year: Option<FxHashMap<String, String>>,
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub this_week: Option<FxHashMap<String, String>>,
 }
 
 impl DateFilterItem {
-    pub fn equals(value: String) -> Self {
+    pub fn equals(value: impl Into<DateValue>) -> Self {
         DateFilterItem {
-            equals: Some(value.to_string()),
+            equals: Some(value.into().into_string()),
             ..Default::default()
         }
     }
 
-    pub fn after(value: String) -> Self {
+    pub fn after(value: impl Into<DateValue>) -> Self {
         DateFilterItem {
-            after: Some(value.to_string()),
+            after: Some(value.into().into_string()),
             ..Default::default()
         }
     }
 
-    pub fn before(value: String) -> Self {
+    pub fn before(value: impl Into<DateValue>) -> Self {
         DateFilterItem {
-            before: Some(value.to_string()),
+            before: Some(value.into().into_string()),
             ..Default::default()
         }
     }
@@ -148,16 +355,80 @@ impl DateFilterItem {
         }
     }
 
-    pub fn on_or_after(value: String) -> Self {
+    pub fn on_or_after(value: impl Into<DateValue>) -> Self {
         DateFilterItem {
-            on_or_after: Some(value.to_string()),
+            on_or_after: Some(value.into().into_string()),
             ..Default::default()
         }
     }
 
-    pub fn on_or_before(value: String) -> Self {
+    pub fn on_or_before(value: impl Into<DateValue>) -> Self {
         DateFilterItem {
-            on_or_before: Some(value.to_string()),
+            on_or_before: Some(value.into().into_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Reject dates that are not valid ISO 8601
+    #[cfg(feature = "chrono")]
+    pub fn validate_dates(&self) -> crate::error::Result<()> {
+        [
+            &self.after,
+            &self.before,
+            &self.equals,
+            &self.on_or_after,
+            &self.on_or_before,
+        ]
+        .into_iter()
+        .flatten()
+        .try_for_each(|value| validate_date(value))
+    }
+
+    pub fn past_week() -> Self {
+        DateFilterItem {
+            past_week: Some(FxHashMap::default()),
+            ..Default::default()
+        }
+    }
+
+    pub fn past_month() -> Self {
+        DateFilterItem {
+            past_month: Some(FxHashMap::default()),
+            ..Default::default()
+        }
+    }
+
+    pub fn past_year() -> Self {
+        DateFilterItem {
+            past_year: Some(FxHashMap::default()),
+            ..Default::default()
+        }
+    }
+
+    pub fn next_week() -> Self {
+        DateFilterItem {
+            next_week: Some(FxHashMap::default()),
+            ..Default::default()
+        }
+    }
+
+    pub fn next_month() -> Self {
+        DateFilterItem {
+            next_month: Some(FxHashMap::default()),
+            ..Default::default()
+        }
+    }
+
+    pub fn next_year() -> Self {
+        DateFilterItem {
+            next_year: Some(FxHashMap::default()),
+            ..Default::default()
+        }
+    }
+
+    pub fn this_week() -> Self {
+        DateFilterItem {
+            this_week: Some(FxHashMap::default()),
             ..Default::default()
         }
     }
@@ -239,6 +510,10 @@ pub struct MultiSelectFilterItem {
     pub equals: Option<bool>,
     #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
     pub does_not_equal: Option<bool>,
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub is_empty: Option<bool>,
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub is_not_empty: Option<bool>,
 }
 
 impl MultiSelectFilterItem {
@@ -269,22 +544,96 @@ impl MultiSelectFilterItem {
             ..Default::default()
         }
     }
+
+    pub fn is_empty() -> Self {
+        MultiSelectFilterItem {
+            is_empty: Some(true),
+            ..Default::default()
+        }
+    }
+
+    pub fn is_not_empty() -> Self {
+        MultiSelectFilterItem {
+            is_not_empty: Some(true),
+            ..Default::default()
+        }
+    }
+}
+
+/// A JSON number used as the operand of a number filter: an integer or a float
+///
+/// Integers too large for an `i64` are converted to a [`FilterNumber::Float`]. `NaN` and infinite
+/// floats have no JSON representation: they are serialized as `null`, which the API rejects, and
+/// are reported by [`QueryFilter::validate`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FilterNumber {
+    Int(i64),
+    Float(f64),
+}
+
+impl FilterNumber {
+    pub fn as_f64(&self) -> f64 {
+        match self {
+            FilterNumber::Int(value) => *value as f64,
+            FilterNumber::Float(value) => *value,
+        }
+    }
+}
+
+macro_rules! impl_from_int_for_filter_number {
+    ($($t:ty),*) => {
+        $(impl From<$t> for FilterNumber {
+            fn from(value: $t) -> Self {
+                FilterNumber::Int(value as i64)
+            }
+        })*
+    };
+}
+
+impl_from_int_for_filter_number!(i8, i16, i32, i64, u8, u16, u32);
+
+macro_rules! impl_from_wide_int_for_filter_number {
+    ($($t:ty),*) => {
+        $(impl From<$t> for FilterNumber {
+            fn from(value: $t) -> Self {
+                i64::try_from(value)
+                    .map(FilterNumber::Int)
+                    .unwrap_or(FilterNumber::Float(value as f64))
+            }
+        })*
+    };
+}
+
+impl_from_wide_int_for_filter_number!(i128, isize, u64, u128, usize);
+
+impl From<f32> for FilterNumber {
+    /// Keeps the shortest decimal form of `value`, so `9.99_f32` is sent as `9.99`
+    fn from(value: f32) -> Self {
+        FilterNumber::Float(value.to_string().parse().unwrap_or(value as f64))
+    }
+}
+
+impl From<f64> for FilterNumber {
+    fn from(value: f64) -> Self {
+        FilterNumber::Float(value)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct NumberFilterItem {
     #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
-    pub equals: Option<u128>,
+    pub equals: Option<FilterNumber>,
     #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
-    pub does_not_equal: Option<u128>,
+    pub does_not_equal: Option<FilterNumber>,
     #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
-    pub greater_than: Option<u128>,
+    pub greater_than: Option<FilterNumber>,
     #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
-    pub less_than: Option<u128>,
+    pub less_than: Option<FilterNumber>,
     #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
-    pub greater_than_or_equal_to: Option<u128>,
+    pub greater_than_or_equal_to: Option<FilterNumber>,
     #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
-    pub less_than_or_equal_to: Option<u128>,
+    pub less_than_or_equal_to: Option<FilterNumber>,
     #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
     pub is_empty: Option<bool>,
     #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
@@ -292,44 +641,59 @@ pub struct NumberFilterItem {
 }
 
 impl NumberFilterItem {
-    pub fn equals(value: u128) -> Self {
+    /// Whether an operand is `NaN` or infinite
+    pub(crate) fn has_non_finite(&self) -> bool {
+        [
+            &self.equals,
+            &self.does_not_equal,
+            &self.greater_than,
+            &self.less_than,
+            &self.greater_than_or_equal_to,
+            &self.less_than_or_equal_to,
+        ]
+        .into_iter()
+        .flatten()
+        .any(|number| !number.as_f64().is_finite())
+    }
+
+    pub fn equals(value: impl Into<FilterNumber>) -> Self {
         NumberFilterItem {
-            equals: Some(value),
+            equals: Some(value.into()),
             ..Default::default()
         }
     }
 
-    pub fn does_not_equal(value: u128) -> Self {
+    pub fn does_not_equal(value: impl Into<FilterNumber>) -> Self {
         NumberFilterItem {
-            does_not_equal: Some(value),
+            does_not_equal: Some(value.into()),
             ..Default::default()
         }
     }
 
-    pub fn greater_than(value: u128) -> Self {
+    pub fn greater_than(value: impl Into<FilterNumber>) -> Self {
         NumberFilterItem {
-            greater_than: Some(value),
+            greater_than: Some(value.into()),
             ..Default::default()
         }
     }
 
-    pub fn less_than(value: u128) -> Self {
+    pub fn less_than(value: impl Into<FilterNumber>) -> Self {
         NumberFilterItem {
-            less_than: Some(value),
+            less_than: Some(value.into()),
             ..Default::default()
         }
     }
 
-    pub fn greater_than_or_equal_to(value: u128) -> Self {
+    pub fn greater_than_or_equal_to(value: impl Into<FilterNumber>) -> Self {
         NumberFilterItem {
-            greater_than_or_equal_to: Some(value),
+            greater_than_or_equal_to: Some(value.into()),
             ..Default::default()
         }
     }
 
-    pub fn less_than_or_equal_to(value: u128) -> Self {
+    pub fn less_than_or_equal_to(value: impl Into<FilterNumber>) -> Self {
         NumberFilterItem {
-            less_than_or_equal_to: Some(value),
+            less_than_or_equal_to: Some(value.into()),
             ..Default::default()
         }
     }
@@ -688,6 +1052,98 @@ impl IdFilterItem {
     }
 }
 
+/// Condition on the values of a rollup property.
+/// `any`, `every` and `none` take a condition on the rolled up property type, e.g. `rich_text`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RollupFilterItem {
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub any: Option<Box<FilterItem>>,
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub every: Option<Box<FilterItem>>,
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub none: Option<Box<FilterItem>>,
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub number: Option<NumberFilterItem>,
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub date: Option<DateFilterItem>,
+}
+
+impl RollupFilterItem {
+    /// At least one rolled up value matches `item`. The `property` of `item` is ignored.
+    pub fn any(item: FilterItem) -> Self {
+        RollupFilterItem {
+            any: Some(Box::new(FilterItem {
+                property: String::new(),
+                ..item
+            })),
+            ..Default::default()
+        }
+    }
+
+    /// Every rolled up value matches `item`. The `property` of `item` is ignored.
+    pub fn every(item: FilterItem) -> Self {
+        RollupFilterItem {
+            every: Some(Box::new(FilterItem {
+                property: String::new(),
+                ..item
+            })),
+            ..Default::default()
+        }
+    }
+
+    /// No rolled up value matches `item`. The `property` of `item` is ignored.
+    pub fn none(item: FilterItem) -> Self {
+        RollupFilterItem {
+            none: Some(Box::new(FilterItem {
+                property: String::new(),
+                ..item
+            })),
+            ..Default::default()
+        }
+    }
+
+    pub fn number(item: NumberFilterItem) -> Self {
+        RollupFilterItem {
+            number: Some(item),
+            ..Default::default()
+        }
+    }
+
+    pub fn date(item: DateFilterItem) -> Self {
+        RollupFilterItem {
+            date: Some(item),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct VerificationFilterItem {
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+impl VerificationFilterItem {
+    /// `value` is one of `verified`, `expired` or `none`
+    pub fn status(value: String) -> Self {
+        VerificationFilterItem {
+            status: Some(value.to_string()),
+        }
+    }
+
+    pub fn verified() -> Self {
+        VerificationFilterItem::status(String::from("verified"))
+    }
+
+    pub fn expired() -> Self {
+        VerificationFilterItem::status(String::from("expired"))
+    }
+
+    pub fn none() -> Self {
+        VerificationFilterItem::status(String::from("none"))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct FilterItem {
     #[serde(default = "String::new", skip_serializing_if = "String::is_empty")]
@@ -722,9 +1178,77 @@ pub struct FilterItem {
     pub timestamp: Option<TimestampFilterItem>,
     #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
     pub id: Option<IdFilterItem>,
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub unique_id: Option<IdFilterItem>,
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub rollup: Option<RollupFilterItem>,
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<PeopleFilterItem>,
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub last_edited_by: Option<PeopleFilterItem>,
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub verification: Option<VerificationFilterItem>,
 }
 
 impl FilterItem {
+    /// `true` if no condition is set
+    pub fn is_empty(&self) -> bool {
+        self.property.is_empty()
+            && self.and.is_none()
+            && self.or.is_none()
+            && self.checkbox.is_none()
+            && self.date.is_none()
+            && self.files.is_none()
+            && self.formula.is_none()
+            && self.multi_select.is_none()
+            && self.number.is_none()
+            && self.people.is_none()
+            && self.relation.is_none()
+            && self.rich_text.is_none()
+            && self.select.is_none()
+            && self.status.is_none()
+            && self.timestamp.is_none()
+            && self.id.is_none()
+            && self.unique_id.is_none()
+            && self.rollup.is_none()
+            && self.created_by.is_none()
+            && self.last_edited_by.is_none()
+            && self.verification.is_none()
+    }
+
+    /// Reject date conditions that are not valid ISO 8601, in this item and every nested item
+    #[cfg(feature = "chrono")]
+    pub fn validate_dates(&self) -> crate::error::Result<()> {
+        for item in self.and.iter().chain(self.or.iter()).flatten() {
+            item.validate_dates()?;
+        }
+        if let Some(rollup) = &self.rollup {
+            for item in [&rollup.any, &rollup.every, &rollup.none]
+                .into_iter()
+                .flatten()
+            {
+                item.validate_dates()?;
+            }
+        }
+        let dates = [
+            self.date.as_ref(),
+            self.formula
+                .as_ref()
+                .and_then(|formula| formula.date.as_ref()),
+            self.timestamp
+                .as_ref()
+                .and_then(|timestamp| timestamp.created_time.as_ref()),
+            self.timestamp
+                .as_ref()
+                .and_then(|timestamp| timestamp.last_edited_time.as_ref()),
+            self.rollup.as_ref().and_then(|rollup| rollup.date.as_ref()),
+        ];
+        dates
+            .into_iter()
+            .flatten()
+            .try_for_each(|date| date.validate_dates())
+    }
+
     pub fn and(items: Vec<FilterItem>) -> Self {
         FilterItem {
             and: Some(items),
@@ -842,20 +1366,350 @@ impl FilterItem {
             ..Default::default()
         }
     }
+
+    pub fn unique_id(property: String, item: IdFilterItem) -> Self {
+        FilterItem {
+            property: property.to_string(),
+            unique_id: Some(item),
+            ..Default::default()
+        }
+    }
+
+    pub fn rollup(property: String, item: RollupFilterItem) -> Self {
+        FilterItem {
+            property: property.to_string(),
+            rollup: Some(item),
+            ..Default::default()
+        }
+    }
+
+    pub fn created_by(property: String, item: PeopleFilterItem) -> Self {
+        FilterItem {
+            property: property.to_string(),
+            created_by: Some(item),
+            ..Default::default()
+        }
+    }
+
+    pub fn last_edited_by(property: String, item: PeopleFilterItem) -> Self {
+        FilterItem {
+            property: property.to_string(),
+            last_edited_by: Some(item),
+            ..Default::default()
+        }
+    }
+
+    pub fn verification(property: String, item: VerificationFilterItem) -> Self {
+        FilterItem {
+            property: property.to_string(),
+            verification: Some(item),
+            ..Default::default()
+        }
+    }
+}
+
+/// Maximum number of nested `and`/`or` levels accepted by the Notion API
+const MAX_NESTING: usize = 2;
+
+/// A problem found by [`QueryFilter::validate`].
+/// `path` locates the offending item, e.g. `filter.and[1].or[0]` or `sorts[2]`.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FilterValidationError {
+    /// No property of the database has this name or ID
+    #[error("{path}: property {property:?} does not exist in the database")]
+    UnknownProperty { path: String, property: String },
+    /// The filter type cannot be applied to the type of the property
+    #[error("{path}: a `{filter}` filter cannot be applied to property {property:?} of type `{property_type}`")]
+    IncompatibleType {
+        path: String,
+        property: String,
+        filter: String,
+        property_type: String,
+    },
+    /// A property filter without a property name
+    #[error("{path}: a `{filter}` filter needs a property")]
+    MissingProperty { path: String, filter: String },
+    /// `and`/`or` nested more than two levels deep
+    #[error("{path}: compound filters can only be nested two levels deep")]
+    TooDeeplyNested { path: String },
+    /// A number filter compares with `NaN` or an infinite number
+    #[error("{path}: number filters need a finite number")]
+    NonFiniteNumber { path: String },
+    /// The condition of a rollup `any`, `every` or `none` is not a single filter on the values
+    #[error("{path}: a `{filter}` condition cannot be applied to the values of a rollup")]
+    InvalidRollupCondition { path: String, filter: String },
+}
+
+/// Filter types accepted by the condition of a rollup `any`, `every` or `none`
+const ROLLUP_CONDITIONS: [&str; 11] = [
+    "checkbox",
+    "date",
+    "files",
+    "multi_select",
+    "number",
+    "people",
+    "relation",
+    "rich_text",
+    "select",
+    "status",
+    "unique_id",
+];
+
+/// Property types a filter type can be applied to
+fn compatible_property_types(filter: &str) -> &'static [&'static str] {
+    match filter {
+        "checkbox" => &["checkbox"],
+        "date" => &["date", "created_time", "last_edited_time"],
+        "files" => &["files"],
+        "formula" => &["formula"],
+        "multi_select" => &["multi_select"],
+        "number" => &["number"],
+        "people" => &["people", "created_by", "last_edited_by"],
+        "relation" => &["relation"],
+        "rich_text" => &["rich_text", "title", "url", "email", "phone_number"],
+        "select" => &["select"],
+        "status" => &["status"],
+        "timestamp" => &["created_time", "last_edited_time"],
+        "id" | "unique_id" => &["unique_id"],
+        "rollup" => &["rollup"],
+        "created_by" => &["created_by"],
+        "last_edited_by" => &["last_edited_by"],
+        "verification" => &["verification"],
+        _ => &[],
+    }
+}
+
+/// Find a property of `database` by name or by ID
+fn find_property<'a>(database: &'a Database, property: &str) -> Option<&'a DatabaseProperty> {
+    database.properties.get(property).or_else(|| {
+        database
+            .properties
+            .values()
+            .find(|candidate| candidate.id == property)
+    })
+}
+
+impl FilterItem {
+    /// Names of the property conditions set on this item
+    pub(crate) fn condition_names(&self) -> Vec<&'static str> {
+        let conditions = [
+            ("checkbox", self.checkbox.is_some()),
+            ("date", self.date.is_some()),
+            ("files", self.files.is_some()),
+            ("formula", self.formula.is_some()),
+            ("multi_select", self.multi_select.is_some()),
+            ("number", self.number.is_some()),
+            ("people", self.people.is_some()),
+            ("relation", self.relation.is_some()),
+            ("rich_text", self.rich_text.is_some()),
+            ("select", self.select.is_some()),
+            ("status", self.status.is_some()),
+            ("timestamp", self.timestamp.is_some()),
+            ("id", self.id.is_some()),
+            ("unique_id", self.unique_id.is_some()),
+            ("rollup", self.rollup.is_some()),
+            ("created_by", self.created_by.is_some()),
+            ("last_edited_by", self.last_edited_by.is_some()),
+            ("verification", self.verification.is_some()),
+        ];
+        conditions
+            .into_iter()
+            .filter(|(_, is_set)| *is_set)
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// Check this item and every nested item against `database`.
+    /// `depth` is the number of `and`/`or` groups this item is nested in.
+    fn validate(
+        &self,
+        database: &Database,
+        path: &str,
+        depth: usize,
+        errors: &mut Vec<FilterValidationError>,
+    ) {
+        for (operator, items) in [("and", &self.and), ("or", &self.or)] {
+            let Some(items) = items else { continue };
+            let path = format!("{path}.{operator}");
+            if depth == MAX_NESTING {
+                errors.push(FilterValidationError::TooDeeplyNested { path: path.clone() });
+            }
+            for (i, item) in items.iter().enumerate() {
+                item.validate(database, &format!("{path}[{i}]"), depth + 1, errors);
+            }
+        }
+
+        for filter in self.condition_names() {
+            if self.property.is_empty() {
+                // timestamp filters apply to the page itself
+                if filter != "timestamp" {
+                    errors.push(FilterValidationError::MissingProperty {
+                        path: path.to_string(),
+                        filter: filter.to_string(),
+                    });
+                }
+                continue;
+            }
+            let Some(property) = find_property(database, &self.property) else {
+                errors.push(FilterValidationError::UnknownProperty {
+                    path: path.to_string(),
+                    property: self.property.clone(),
+                });
+                break;
+            };
+            if !compatible_property_types(filter).contains(&property.type_name.as_str()) {
+                errors.push(FilterValidationError::IncompatibleType {
+                    path: path.to_string(),
+                    property: self.property.clone(),
+                    filter: filter.to_string(),
+                    property_type: property.type_name.clone(),
+                });
+            }
+        }
+
+        if let Some(rollup) = &self.rollup {
+            for (name, item) in [
+                ("any", &rollup.any),
+                ("every", &rollup.every),
+                ("none", &rollup.none),
+            ] {
+                if let Some(item) = item {
+                    item.validate_rollup_condition(&format!("{path}.rollup.{name}"), errors);
+                }
+            }
+        }
+        self.validate_numbers(path, errors);
+    }
+
+    /// Check the condition of a rollup `any`, `every` or `none`; its property is ignored
+    fn validate_rollup_condition(&self, path: &str, errors: &mut Vec<FilterValidationError>) {
+        let compounds = [("and", self.and.is_some()), ("or", self.or.is_some())]
+            .into_iter()
+            .filter(|(_, is_set)| *is_set)
+            .map(|(name, _)| name);
+        let conditions = self
+            .condition_names()
+            .into_iter()
+            .filter(|filter| !ROLLUP_CONDITIONS.contains(filter));
+        for filter in compounds.chain(conditions) {
+            errors.push(FilterValidationError::InvalidRollupCondition {
+                path: path.to_string(),
+                filter: filter.to_string(),
+            });
+        }
+        self.validate_numbers(path, errors);
+    }
+
+    fn validate_numbers(&self, path: &str, errors: &mut Vec<FilterValidationError>) {
+        let numbers = [
+            self.number.as_ref(),
+            self.formula
+                .as_ref()
+                .and_then(|formula| formula.number.as_ref()),
+            self.rollup
+                .as_ref()
+                .and_then(|rollup| rollup.number.as_ref()),
+        ];
+        if numbers
+            .into_iter()
+            .flatten()
+            .any(NumberFilterItem::has_non_finite)
+        {
+            errors.push(FilterValidationError::NonFiniteNumber {
+                path: path.to_string(),
+            });
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub enum SortDirection {
+    #[default]
+    #[serde(rename = "ascending")]
+    Ascending,
+    #[serde(rename = "descending")]
+    Descending,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SortTimestamp {
+    #[serde(rename = "created_time")]
+    CreatedTime,
+    #[serde(rename = "last_edited_time")]
+    LastEditedTime,
+}
+
+/// Sort the results of a query by a property or by a timestamp
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Sort {
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub property: Option<String>,
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<SortTimestamp>,
+    #[serde(default = "SortDirection::default")]
+    pub direction: SortDirection,
+}
+
+impl Sort {
+    pub fn property(property: String, direction: SortDirection) -> Self {
+        Sort {
+            property: Some(property.to_string()),
+            direction,
+            ..Default::default()
+        }
+    }
+
+    pub fn ascending(property: String) -> Self {
+        Sort::property(property, SortDirection::Ascending)
+    }
+
+    pub fn descending(property: String) -> Self {
+        Sort::property(property, SortDirection::Descending)
+    }
+
+    pub fn created_time(direction: SortDirection) -> Self {
+        Sort {
+            timestamp: Some(SortTimestamp::CreatedTime),
+            direction,
+            ..Default::default()
+        }
+    }
+
+    pub fn last_edited_time(direction: SortDirection) -> Self {
+        Sort {
+            timestamp: Some(SortTimestamp::LastEditedTime),
+            direction,
+            ..Default::default()
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueryFilter {
+    #[serde(
+        default = "FilterItem::default",
+        skip_serializing_if = "FilterItem::is_empty"
+    )]
     pub filter: FilterItem,
+    #[serde(default = "Vec::default", skip_serializing_if = "Vec::is_empty")]
+    pub sorts: Vec<Sort>,
     #[serde(default = "String::new", skip_serializing_if = "String::is_empty")]
     pub start_cursor: String,
+    #[serde(default = "Option::default", skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<u32>,
+    /// Property IDs to return, sent as the `filter_properties` query parameter
+    #[serde(skip)]
+    pub filter_properties: Vec<String>,
 }
 
 impl QueryFilter {
     pub fn new() -> Self {
         QueryFilter {
             filter: FilterItem::default(),
+            sorts: Vec::new(),
             start_cursor: String::new(),
+            page_size: None,
+            filter_properties: Vec::new(),
         }
     }
 
@@ -871,7 +1725,1182 @@ impl QueryFilter {
         self.filter.or = Some(items);
     }
 
+    /// Add a sort. Sorts are applied in the order they are added:
+    /// the first one takes precedence over the following ones.
+    pub fn sort(&mut self, sort: Sort) {
+        self.sorts.push(sort);
+    }
+
+    /// Number of pages per response, at most 100
+    pub fn page_size(&mut self, page_size: u32) {
+        self.page_size = Some(page_size);
+    }
+
+    /// Only return the given properties of each page.  
+    /// Property IDs are found in [`DatabaseProperty::id`](crate::structs::database::DatabaseProperty).
+    /// Pass them exactly as the API returns them, percent-encoded (e.g. `%3AUPp`): they are
+    /// decoded and re-encoded when the request URL is built.
+    pub fn filter_properties(&mut self, property_ids: Vec<String>) {
+        self.filter_properties = property_ids;
+    }
+
+    /// Reject date conditions that are not valid ISO 8601
+    #[cfg(feature = "chrono")]
+    pub fn validate_dates(&self) -> crate::error::Result<()> {
+        self.filter.validate_dates()
+    }
+
+    /// Check the filter and sorts against the schema of `database` before sending the query.
+    /// Properties are looked up by name or ID, filter types must match the property types and
+    /// `and`/`or` groups may be nested at most two levels deep.
+    ///
+    /// ## Arguments:
+    /// - database: [`Database`] returned by [`Notion::retrieve_a_database`](crate::Notion::retrieve_a_database)
+    /// ## Return:
+    /// - every problem found, in the order of the filter tree
+    pub fn validate(&self, database: &Database) -> Result<(), Vec<FilterValidationError>> {
+        let mut errors = Vec::new();
+        if !self.filter.is_empty() {
+            self.filter.validate(database, "filter", 0, &mut errors);
+        }
+        for (i, sort) in self.sorts.iter().enumerate() {
+            if let Some(property) = &sort.property {
+                if find_property(database, property).is_none() {
+                    errors.push(FilterValidationError::UnknownProperty {
+                        path: format!("sorts[{i}]"),
+                        property: property.clone(),
+                    });
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
     pub fn build(&self) -> String {
         serde_json::to_string(self).unwrap()
     }
 }
+
+/// Error returned when a filter expression cannot be parsed
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{message} at position {position}")]
+pub struct FilterParseError {
+    /// Byte offset in the expression
+    pub position: usize,
+    pub message: String,
+}
+
+impl FilterParseError {
+    fn new(position: usize, message: impl Into<String>) -> Self {
+        FilterParseError {
+            position,
+            message: message.into(),
+        }
+    }
+}
+
+/// Relative date conditions accepted after `in`
+const RELATIVE_DATES: [&str; 7] = [
+    "past_week",
+    "past_month",
+    "past_year",
+    "next_week",
+    "next_month",
+    "next_year",
+    "this_week",
+];
+
+/// Words with a meaning in the query language. Property names equal to one of them must be quoted.
+const KEYWORDS: [&str; 16] = [
+    "and", "or", "not", "contains", "starts", "ends", "with", "has", "is", "empty", "in", "any",
+    "every", "none", "true", "false",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Quoted string
+    Text(String),
+    /// Unquoted word, number or date
+    Word(String),
+    Symbol(&'static str),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Text(String),
+    Number(String),
+    Bool(bool),
+}
+
+#[derive(Debug)]
+enum Operator {
+    Equals(Value),
+    NotEquals(Value),
+    GreaterThan(Value),
+    GreaterThanOrEquals(Value),
+    LessThan(Value),
+    LessThanOrEquals(Value),
+    Contains(Value),
+    NotContains(Value),
+    StartsWith(Value),
+    EndsWith(Value),
+    Has(Value),
+    NotHas(Value),
+    IsEmpty,
+    IsNotEmpty,
+    In(String),
+    Any(FilterItem),
+    Every(FilterItem),
+    None(FilterItem),
+}
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, FilterParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some(&(position, c)) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '"' => {
+                chars.next();
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, c)) => text.push(c),
+                            None => {
+                                return Err(FilterParseError::new(position, "unterminated string"))
+                            }
+                        },
+                        Some((_, c)) => text.push(c),
+                        None => return Err(FilterParseError::new(position, "unterminated string")),
+                    }
+                }
+                tokens.push((position, Token::Text(text)));
+            }
+            '(' | ')' | ':' | '=' => {
+                chars.next();
+                let symbol = match c {
+                    '(' => "(",
+                    ')' => ")",
+                    ':' => ":",
+                    _ => "=",
+                };
+                tokens.push((position, Token::Symbol(symbol)));
+            }
+            '!' | '<' | '>' => {
+                chars.next();
+                let with_equals = chars.next_if(|&(_, next)| next == '=').is_some();
+                let symbol = match (c, with_equals) {
+                    ('!', true) => "!=",
+                    ('<', true) => "<=",
+                    ('<', false) => "<",
+                    ('>', true) => ">=",
+                    ('>', false) => ">",
+                    _ => return Err(FilterParseError::new(position, "expected `!=`")),
+                };
+                tokens.push((position, Token::Symbol(symbol)));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&(index, c)) = chars.peek() {
+                    // the `:` of an unquoted date-time such as 2024-01-01T10:00:00Z
+                    let is_time = c == ':'
+                        && is_date_time(&word)
+                        && input[index + 1..].starts_with(|c: char| c.is_ascii_digit());
+                    if !is_time && (c.is_whitespace() || "\"():=!<>".contains(c)) {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push((position, Token::Word(word)));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Whether `word` starts with an ISO 8601 date and a time, e.g. `2024-01-01T10`
+fn is_date_time(word: &str) -> bool {
+    let bytes = word.as_bytes();
+    bytes.len() > 10
+        && bytes[..10]
+            .iter()
+            .enumerate()
+            .all(|(index, byte)| match index {
+                4 | 7 => *byte == b'-',
+                _ => byte.is_ascii_digit(),
+            })
+        && bytes[10] == b'T'
+}
+
+struct Parser<'a> {
+    tokens: Vec<(usize, Token)>,
+    index: usize,
+    end: usize,
+    database: Option<&'a Database>,
+    /// Inside a rollup `any`/`every`/`none`, where conditions have no property
+    in_rollup: bool,
+}
+
+impl Parser<'_> {
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.index)
+            .map_or(self.end, |(position, _)| *position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.index).map(|(_, token)| token.clone());
+        self.index += 1;
+        token
+    }
+
+    /// Consume the next token if it is the keyword `keyword`
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.tokens.get(self.index) {
+            Some((_, Token::Word(word))) if word.eq_ignore_ascii_case(keyword) => {
+                self.index += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn symbol(&mut self, symbol: &str) -> bool {
+        match self.tokens.get(self.index) {
+            Some((_, Token::Symbol(s))) if *s == symbol => {
+                self.index += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), FilterParseError> {
+        if self.keyword(keyword) {
+            Ok(())
+        } else {
+            Err(FilterParseError::new(
+                self.position(),
+                format!("expected `{keyword}`"),
+            ))
+        }
+    }
+
+    fn parse(&mut self) -> Result<FilterItem, FilterParseError> {
+        if self.tokens.is_empty() {
+            return Ok(FilterItem::default());
+        }
+        let item = self.or()?;
+        if self.index < self.tokens.len() {
+            return Err(FilterParseError::new(
+                self.position(),
+                "expected `and`, `or` or the end of the expression",
+            ));
+        }
+        Ok(item)
+    }
+
+    fn or(&mut self) -> Result<FilterItem, FilterParseError> {
+        let mut items = vec![self.and()?];
+        while self.keyword("or") {
+            items.push(self.and()?);
+        }
+        Ok(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            FilterItem::or(items)
+        })
+    }
+
+    fn and(&mut self) -> Result<FilterItem, FilterParseError> {
+        let mut items = vec![self.atom()?];
+        while self.keyword("and") {
+            items.push(self.atom()?);
+        }
+        Ok(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            FilterItem::and(items)
+        })
+    }
+
+    fn atom(&mut self) -> Result<FilterItem, FilterParseError> {
+        if self.symbol("(") {
+            let item = self.or()?;
+            if !self.symbol(")") {
+                return Err(FilterParseError::new(self.position(), "expected `)`"));
+            }
+            return Ok(item);
+        }
+        self.condition()
+    }
+
+    fn condition(&mut self) -> Result<FilterItem, FilterParseError> {
+        let position = self.position();
+        let has_property = !self.in_rollup
+            || matches!(
+                self.tokens.get(self.index + 1),
+                Some((_, Token::Symbol(":")))
+            );
+        if !has_property {
+            // `any (status = "Done")`: the condition applies to the rolled up values
+            let type_name = match self.next() {
+                Some(Token::Word(word)) => word.to_lowercase(),
+                _ => return Err(FilterParseError::new(position, "expected a property type")),
+            };
+            let operator = self.operator()?;
+            return build_condition(String::new(), &type_name, operator)
+                .map_err(|message| FilterParseError::new(position, message));
+        }
+        let property = match self.next() {
+            Some(Token::Text(text)) => text,
+            Some(Token::Word(word)) => word,
+            _ => return Err(FilterParseError::new(position, "expected a property name")),
+        };
+        let type_name = if self.symbol(":") {
+            match self.next() {
+                Some(Token::Word(word)) => Some(word.to_lowercase()),
+                _ => {
+                    return Err(FilterParseError::new(
+                        self.position(),
+                        "expected a property type",
+                    ))
+                }
+            }
+        } else {
+            None
+        };
+
+        let operator = self.operator()?;
+        let type_name = match type_name {
+            Some(type_name) => type_name,
+            None => self
+                .infer_type(&property, &operator)
+                .ok_or_else(|| {
+                    FilterParseError::new(
+                        position,
+                        format!("cannot infer the type of property {property:?}, write it as `{property}:<type>`"),
+                    )
+                })?,
+        };
+        build_condition(property, &type_name, operator)
+            .map_err(|message| FilterParseError::new(position, message))
+    }
+
+    fn value(&mut self) -> Result<Value, FilterParseError> {
+        let position = self.position();
+        match self.next() {
+            Some(Token::Text(text)) => Ok(Value::Text(text)),
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case("true") => Ok(Value::Bool(true)),
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case("false") => Ok(Value::Bool(false)),
+            Some(Token::Word(word))
+                if word.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '.')
+                    && word.parse::<f64>().is_ok() =>
+            {
+                if word.parse::<f64>().is_ok_and(f64::is_finite) {
+                    Ok(Value::Number(word))
+                } else {
+                    Err(FilterParseError::new(
+                        position,
+                        format!("{} is not a finite number", word),
+                    ))
+                }
+            }
+            Some(Token::Word(word)) => Ok(Value::Text(word)),
+            _ => Err(FilterParseError::new(position, "expected a value")),
+        }
+    }
+
+    fn operator(&mut self) -> Result<Operator, FilterParseError> {
+        let position = self.position();
+        let operator = match self.next() {
+            Some(Token::Symbol("=")) => Operator::Equals(self.value()?),
+            Some(Token::Symbol("!=")) => Operator::NotEquals(self.value()?),
+            Some(Token::Symbol(">")) => Operator::GreaterThan(self.value()?),
+            Some(Token::Symbol(">=")) => Operator::GreaterThanOrEquals(self.value()?),
+            Some(Token::Symbol("<")) => Operator::LessThan(self.value()?),
+            Some(Token::Symbol("<=")) => Operator::LessThanOrEquals(self.value()?),
+            Some(Token::Word(word)) => match word.to_lowercase().as_str() {
+                "contains" => Operator::Contains(self.value()?),
+                "has" => Operator::Has(self.value()?),
+                "not" if self.keyword("contains") => Operator::NotContains(self.value()?),
+                "not" if self.keyword("has") => Operator::NotHas(self.value()?),
+                "starts" => {
+                    self.expect_keyword("with")?;
+                    Operator::StartsWith(self.value()?)
+                }
+                "ends" => {
+                    self.expect_keyword("with")?;
+                    Operator::EndsWith(self.value()?)
+                }
+                "is" => {
+                    let not = self.keyword("not");
+                    self.expect_keyword("empty")?;
+                    if not {
+                        Operator::IsNotEmpty
+                    } else {
+                        Operator::IsEmpty
+                    }
+                }
+                "in" => {
+                    let position = self.position();
+                    match self.next() {
+                        Some(Token::Word(word))
+                            if RELATIVE_DATES.contains(&word.to_lowercase().as_str()) =>
+                        {
+                            Operator::In(word.to_lowercase())
+                        }
+                        _ => {
+                            return Err(FilterParseError::new(
+                                position,
+                                format!("expected one of {}", RELATIVE_DATES.join(", ")),
+                            ))
+                        }
+                    }
+                }
+                "any" => Operator::Any(self.rollup_condition()?),
+                "every" => Operator::Every(self.rollup_condition()?),
+                "none" => Operator::None(self.rollup_condition()?),
+                _ => return Err(FilterParseError::new(position, "expected an operator")),
+            },
+            _ => return Err(FilterParseError::new(position, "expected an operator")),
+        };
+        Ok(operator)
+    }
+
+    /// Condition after a rollup `any`/`every`/`none`
+    fn rollup_condition(&mut self) -> Result<FilterItem, FilterParseError> {
+        let in_rollup = std::mem::replace(&mut self.in_rollup, true);
+        let item = self.atom();
+        self.in_rollup = in_rollup;
+        item
+    }
+
+    /// Type of the filter on `property`, from the database schema if any, or from the operator
+    fn infer_type(&self, property: &str, operator: &Operator) -> Option<String> {
+        let value = match operator {
+            Operator::Equals(value)
+            | Operator::NotEquals(value)
+            | Operator::GreaterThan(value)
+            | Operator::GreaterThanOrEquals(value)
+            | Operator::LessThan(value)
+            | Operator::LessThanOrEquals(value) => Some(value),
+            _ => None,
+        };
+        let is_comparison = matches!(
+            operator,
+            Operator::GreaterThan(_)
+                | Operator::GreaterThanOrEquals(_)
+                | Operator::LessThan(_)
+                | Operator::LessThanOrEquals(_)
+        );
+        let by_value = match (operator, value) {
+            (Operator::In(_), _) => Some("date"),
+            (Operator::Any(_) | Operator::Every(_) | Operator::None(_), _) => Some("rollup"),
+            (_, Some(Value::Bool(_))) => Some("checkbox"),
+            (_, Some(Value::Number(_))) => Some("number"),
+            (_, Some(Value::Text(_))) if is_comparison => Some("date"),
+            (Operator::Has(_) | Operator::NotHas(_), _) => Some("multi_select"),
+            (
+                Operator::Contains(_)
+                | Operator::NotContains(_)
+                | Operator::StartsWith(_)
+                | Operator::EndsWith(_),
+                _,
+            ) => Some("rich_text"),
+            (Operator::Equals(_) | Operator::NotEquals(_), Some(Value::Text(_))) => {
+                Some("rich_text")
+            }
+            _ => None,
+        };
+
+        let schema_type = self
+            .database
+            .and_then(|database| find_property(database, property))
+            .map(|property| property.type_name.as_str());
+        let type_name = match schema_type {
+            Some("title" | "rich_text" | "url" | "email" | "phone_number") => "rich_text",
+            Some("formula") => match by_value {
+                Some("checkbox") => "formula.checkbox",
+                Some("number") => "formula.number",
+                Some("date") => "formula.date",
+                _ => "formula.string",
+            },
+            Some("rollup") => match by_value {
+                Some("date") => "rollup.date",
+                Some("rollup") => "rollup",
+                _ => "rollup.number",
+            },
+            Some(type_name) => type_name,
+            None => by_value?,
+        };
+        Some(type_name.to_string())
+    }
+}
+
+fn number_value(value: Value) -> Result<FilterNumber, String> {
+    match value {
+        Value::Number(number) => match number.parse::<i64>() {
+            Ok(number) => Ok(FilterNumber::Int(number)),
+            Err(_) => number
+                .parse::<f64>()
+                .ok()
+                .filter(|number| number.is_finite())
+                .map(FilterNumber::Float)
+                .ok_or_else(|| format!("{} is not a finite number", number)),
+        },
+        _ => Err(String::from("expected a number")),
+    }
+}
+
+fn text_value(value: Value) -> String {
+    match value {
+        Value::Text(text) | Value::Number(text) => text,
+        Value::Bool(value) => value.to_string(),
+    }
+}
+
+fn unsupported(type_name: &str) -> String {
+    format!("operator not supported by `{type_name}` filters")
+}
+
+fn checkbox_item(operator: Operator) -> Result<CheckboxFilterItem, String> {
+    match operator {
+        Operator::Equals(Value::Bool(value)) => Ok(CheckboxFilterItem::is(value)),
+        Operator::NotEquals(Value::Bool(value)) => Ok(CheckboxFilterItem::is_not(value)),
+        Operator::Equals(_) | Operator::NotEquals(_) => Err(String::from("expected true or false")),
+        _ => Err(unsupported("checkbox")),
+    }
+}
+
+fn date_item(operator: Operator) -> Result<DateFilterItem, String> {
+    match operator {
+        Operator::Equals(value) => Ok(DateFilterItem::equals(text_value(value))),
+        Operator::LessThan(value) => Ok(DateFilterItem::before(text_value(value))),
+        Operator::GreaterThan(value) => Ok(DateFilterItem::after(text_value(value))),
+        Operator::LessThanOrEquals(value) => Ok(DateFilterItem::on_or_before(text_value(value))),
+        Operator::GreaterThanOrEquals(value) => Ok(DateFilterItem::on_or_after(text_value(value))),
+        Operator::IsEmpty => Ok(DateFilterItem::is_empty()),
+        Operator::IsNotEmpty => Ok(DateFilterItem::is_not_empty()),
+        Operator::In(relative) => Ok(match relative.as_str() {
+            "past_week" => DateFilterItem::past_week(),
+            "past_month" => DateFilterItem::past_month(),
+            "past_year" => DateFilterItem::past_year(),
+            "next_week" => DateFilterItem::next_week(),
+            "next_month" => DateFilterItem::next_month(),
+            "next_year" => DateFilterItem::next_year(),
+            _ => DateFilterItem::this_week(),
+        }),
+        _ => Err(unsupported("date")),
+    }
+}
+
+fn number_item(operator: Operator) -> Result<NumberFilterItem, String> {
+    match operator {
+        Operator::Equals(value) => Ok(NumberFilterItem::equals(number_value(value)?)),
+        Operator::NotEquals(value) => Ok(NumberFilterItem::does_not_equal(number_value(value)?)),
+        Operator::GreaterThan(value) => Ok(NumberFilterItem::greater_than(number_value(value)?)),
+        Operator::LessThan(value) => Ok(NumberFilterItem::less_than(number_value(value)?)),
+        Operator::GreaterThanOrEquals(value) => Ok(NumberFilterItem::greater_than_or_equal_to(
+            number_value(value)?,
+        )),
+        Operator::LessThanOrEquals(value) => Ok(NumberFilterItem::less_than_or_equal_to(
+            number_value(value)?,
+        )),
+        Operator::IsEmpty => Ok(NumberFilterItem::is_empty()),
+        Operator::IsNotEmpty => Ok(NumberFilterItem::is_not_empty()),
+        _ => Err(unsupported("number")),
+    }
+}
+
+fn rich_text_item(operator: Operator) -> Result<RichTextFilterItem, String> {
+    match operator {
+        Operator::Equals(value) => Ok(RichTextFilterItem::equals(text_value(value))),
+        Operator::NotEquals(value) => Ok(RichTextFilterItem::does_not_equal(text_value(value))),
+        Operator::Contains(value) => Ok(RichTextFilterItem::contains(text_value(value))),
+        Operator::NotContains(value) => Ok(RichTextFilterItem::does_not_contain(text_value(value))),
+        Operator::StartsWith(value) => Ok(RichTextFilterItem::starts_with(text_value(value))),
+        Operator::EndsWith(value) => Ok(RichTextFilterItem::ends_with(text_value(value))),
+        Operator::IsEmpty => Ok(RichTextFilterItem::is_empty()),
+        Operator::IsNotEmpty => Ok(RichTextFilterItem::is_not_empty()),
+        _ => Err(unsupported("rich_text")),
+    }
+}
+
+fn people_item(operator: Operator) -> Result<PeopleFilterItem, String> {
+    match operator {
+        Operator::Has(value) | Operator::Contains(value) => {
+            Ok(PeopleFilterItem::contains(text_value(value)))
+        }
+        Operator::NotHas(value) | Operator::NotContains(value) => {
+            Ok(PeopleFilterItem::does_not_contain(text_value(value)))
+        }
+        Operator::IsEmpty => Ok(PeopleFilterItem::is_empty()),
+        Operator::IsNotEmpty => Ok(PeopleFilterItem::is_not_empty()),
+        _ => Err(unsupported("people")),
+    }
+}
+
+fn id_item(operator: Operator) -> Result<IdFilterItem, String> {
+    let id = |value: Value| match value {
+        Value::Number(number) => number
+            .parse::<u128>()
+            .map_err(|_| String::from("expected a positive integer")),
+        _ => Err(String::from("expected a positive integer")),
+    };
+    match operator {
+        Operator::Equals(value) => Ok(IdFilterItem::equals(id(value)?)),
+        Operator::NotEquals(value) => Ok(IdFilterItem::does_not_equal(id(value)?)),
+        Operator::GreaterThan(value) => Ok(IdFilterItem::greater_than(id(value)?)),
+        Operator::LessThan(value) => Ok(IdFilterItem::less_than(id(value)?)),
+        Operator::GreaterThanOrEquals(value) => {
+            Ok(IdFilterItem::greater_than_or_equal_to(id(value)?))
+        }
+        Operator::LessThanOrEquals(value) => Ok(IdFilterItem::less_than_or_equal_to(id(value)?)),
+        _ => Err(unsupported("unique_id")),
+    }
+}
+
+fn build_condition(
+    property: String,
+    type_name: &str,
+    operator: Operator,
+) -> Result<FilterItem, String> {
+    let item = match type_name {
+        "checkbox" => FilterItem::checkbox(property, checkbox_item(operator)?),
+        "date" => FilterItem::date(property, date_item(operator)?),
+        "created_time" => FilterItem::timestamp(
+            property,
+            TimestampFilterItem::created_time(date_item(operator)?),
+        ),
+        "last_edited_time" => FilterItem::timestamp(
+            property,
+            TimestampFilterItem::last_edited_time(date_item(operator)?),
+        ),
+        "files" => match operator {
+            Operator::IsEmpty => FilterItem::files(property, FilesFilterItem::is_empty()),
+            Operator::IsNotEmpty => FilterItem::files(property, FilesFilterItem::is_not_empty()),
+            _ => return Err(unsupported(type_name)),
+        },
+        "formula.checkbox" => FilterItem::formula(
+            property,
+            FormulaFilterItem::checkbox(checkbox_item(operator)?),
+        ),
+        "formula.date" => {
+            FilterItem::formula(property, FormulaFilterItem::date(date_item(operator)?))
+        }
+        "formula.number" => {
+            FilterItem::formula(property, FormulaFilterItem::number(number_item(operator)?))
+        }
+        "formula.string" => FilterItem::formula(
+            property,
+            FormulaFilterItem::string(rich_text_item(operator)?),
+        ),
+        "multi_select" => FilterItem::multi_select(
+            property,
+            match operator {
+                Operator::Has(value) | Operator::Contains(value) => {
+                    MultiSelectFilterItem::contains(text_value(value))
+                }
+                Operator::NotHas(value) | Operator::NotContains(value) => {
+                    MultiSelectFilterItem::does_not_contain(text_value(value))
+                }
+                Operator::IsEmpty => MultiSelectFilterItem::is_empty(),
+                Operator::IsNotEmpty => MultiSelectFilterItem::is_not_empty(),
+                _ => return Err(unsupported(type_name)),
+            },
+        ),
+        "number" => FilterItem::number(property, number_item(operator)?),
+        "people" => FilterItem::people(property, people_item(operator)?),
+        "created_by" => FilterItem::created_by(property, people_item(operator)?),
+        "last_edited_by" => FilterItem::last_edited_by(property, people_item(operator)?),
+        "relation" => FilterItem::relation(
+            property,
+            match operator {
+                Operator::Has(value) | Operator::Contains(value) => {
+                    RelationFilterItem::contains(text_value(value))
+                }
+                Operator::NotHas(value) | Operator::NotContains(value) => {
+                    RelationFilterItem::does_not_contain(text_value(value))
+                }
+                Operator::IsEmpty => RelationFilterItem::is_empty(),
+                Operator::IsNotEmpty => RelationFilterItem::is_not_empty(),
+                _ => return Err(unsupported(type_name)),
+            },
+        ),
+        "rich_text" | "text" | "title" | "url" | "email" | "phone_number" => {
+            FilterItem::rich_text(property, rich_text_item(operator)?)
+        }
+        "rollup" => FilterItem::rollup(
+            property,
+            match operator {
+                Operator::Any(item) => RollupFilterItem::any(item),
+                Operator::Every(item) => RollupFilterItem::every(item),
+                Operator::None(item) => RollupFilterItem::none(item),
+                _ => return Err(unsupported(type_name)),
+            },
+        ),
+        "rollup.date" => FilterItem::rollup(property, RollupFilterItem::date(date_item(operator)?)),
+        "rollup.number" => {
+            FilterItem::rollup(property, RollupFilterItem::number(number_item(operator)?))
+        }
+        "select" => FilterItem::select(
+            property,
+            match operator {
+                Operator::Equals(value) => SelectFilterItem::equals(text_value(value)),
+                Operator::NotEquals(value) => SelectFilterItem::does_not_equals(text_value(value)),
+                Operator::IsEmpty => SelectFilterItem::is_empty(),
+                Operator::IsNotEmpty => SelectFilterItem::is_not_empty(),
+                _ => return Err(unsupported(type_name)),
+            },
+        ),
+        "status" => FilterItem::status(
+            property,
+            match operator {
+                Operator::Equals(value) => StatusFilterItem::equals(text_value(value)),
+                Operator::NotEquals(value) => StatusFilterItem::does_not_equals(text_value(value)),
+                Operator::IsEmpty => StatusFilterItem::is_empty(),
+                Operator::IsNotEmpty => StatusFilterItem::is_not_empty(),
+                _ => return Err(unsupported(type_name)),
+            },
+        ),
+        "id" => FilterItem::id(property, id_item(operator)?),
+        "unique_id" => FilterItem::unique_id(property, id_item(operator)?),
+        "verification" => match operator {
+            Operator::Equals(value) => FilterItem::verification(
+                property,
+                VerificationFilterItem::status(text_value(value)),
+            ),
+            _ => return Err(unsupported(type_name)),
+        },
+        _ => return Err(format!("unknown property type `{type_name}`")),
+    };
+    Ok(item)
+}
+
+impl FilterItem {
+    /// Parse a filter expression such as
+    /// `Status:status = "ToDo" and (Priority >= 2 or Tags has "urgent")`.
+    /// See the [module documentation](self) for the syntax.
+    pub fn parse(input: &str) -> Result<Self, FilterParseError> {
+        Parser {
+            tokens: tokenize(input)?,
+            index: 0,
+            end: input.len(),
+            database: None,
+            in_rollup: false,
+        }
+        .parse()
+    }
+
+    /// Parse a filter expression, taking the type of each property from `database`
+    /// when it is not written in the expression
+    pub fn parse_with_schema(input: &str, database: &Database) -> Result<Self, FilterParseError> {
+        Parser {
+            tokens: tokenize(input)?,
+            index: 0,
+            end: input.len(),
+            database: Some(database),
+            in_rollup: false,
+        }
+        .parse()
+    }
+
+    /// Conditions that must all hold, rendered in the query language.
+    /// The flag is `true` for `or` groups, which need parentheses next to other conditions.
+    /// Conditions inside a rollup are written without their property.
+    fn clauses(&self, in_rollup: bool) -> Vec<(String, bool)> {
+        let mut clauses = Vec::new();
+        for item in self.and.iter().flatten() {
+            clauses.extend(item.clauses(in_rollup));
+        }
+        if let Some(items) = self.or.as_ref().filter(|items| !items.is_empty()) {
+            // empty items match everything and have no text form
+            let alternatives: Vec<String> = items
+                .iter()
+                .filter_map(|item| match item.clauses(in_rollup).as_slice() {
+                    [] => None,
+                    [(clause, _)] => Some(clause.clone()),
+                    clauses => Some(format!("({})", join_clauses(clauses))),
+                })
+                .collect();
+            if !alternatives.is_empty() {
+                clauses.push((alternatives.join(" or "), alternatives.len() > 1));
+            }
+        }
+
+        let property = quote_property(&self.property);
+        let mut push = |type_name: &str, condition: String| {
+            let clause = if in_rollup {
+                format!("{type_name} {condition}")
+            } else {
+                format!("{property}:{type_name} {condition}")
+            };
+            clauses.push((clause, false));
+        };
+        if let Some(item) = &self.checkbox {
+            checkbox_conditions(item)
+                .into_iter()
+                .for_each(|c| push("checkbox", c));
+        }
+        if let Some(item) = &self.date {
+            date_conditions(item)
+                .into_iter()
+                .for_each(|c| push("date", c));
+        }
+        if let Some(item) = &self.files {
+            empty_conditions(item.is_empty, item.is_not_empty)
+                .into_iter()
+                .for_each(|c| push("files", c));
+        }
+        if let Some(item) = &self.formula {
+            if let Some(item) = &item.checkbox {
+                checkbox_conditions(item)
+                    .into_iter()
+                    .for_each(|c| push("formula.checkbox", c));
+            }
+            if let Some(item) = &item.date {
+                date_conditions(item)
+                    .into_iter()
+                    .for_each(|c| push("formula.date", c));
+            }
+            if let Some(item) = &item.number {
+                number_conditions(item)
+                    .into_iter()
+                    .for_each(|c| push("formula.number", c));
+            }
+            if let Some(item) = &item.string {
+                rich_text_conditions(item)
+                    .into_iter()
+                    .for_each(|c| push("formula.string", c));
+            }
+        }
+        if let Some(item) = &self.multi_select {
+            has_conditions(
+                &item.contains,
+                &item.does_not_contain,
+                item.is_empty,
+                item.is_not_empty,
+            )
+            .into_iter()
+            .for_each(|c| push("multi_select", c));
+        }
+        if let Some(item) = &self.number {
+            number_conditions(item)
+                .into_iter()
+                .for_each(|c| push("number", c));
+        }
+        for (type_name, item) in [
+            ("people", &self.people),
+            ("created_by", &self.created_by),
+            ("last_edited_by", &self.last_edited_by),
+        ] {
+            if let Some(item) = item {
+                has_conditions(
+                    &item.contains,
+                    &item.does_not_contain,
+                    item.is_empty,
+                    item.is_not_empty,
+                )
+                .into_iter()
+                .for_each(|c| push(type_name, c));
+            }
+        }
+        if let Some(item) = &self.relation {
+            has_conditions(
+                &item.contains,
+                &item.does_not_contain,
+                item.is_empty,
+                item.is_not_empty,
+            )
+            .into_iter()
+            .for_each(|c| push("relation", c));
+        }
+        if let Some(item) = &self.rich_text {
+            rich_text_conditions(item)
+                .into_iter()
+                .for_each(|c| push("rich_text", c));
+        }
+        if let Some(item) = &self.select {
+            option_conditions(
+                &item.equals,
+                &item.does_not_equals,
+                item.is_empty,
+                item.is_not_empty,
+            )
+            .into_iter()
+            .for_each(|c| push("select", c));
+        }
+        if let Some(item) = &self.status {
+            option_conditions(
+                &item.equals,
+                &item.does_not_equals,
+                item.is_empty,
+                item.is_not_empty,
+            )
+            .into_iter()
+            .for_each(|c| push("status", c));
+        }
+        if let Some(item) = &self.timestamp {
+            if let Some(item) = &item.created_time {
+                date_conditions(item)
+                    .into_iter()
+                    .for_each(|c| push("created_time", c));
+            }
+            if let Some(item) = &item.last_edited_time {
+                date_conditions(item)
+                    .into_iter()
+                    .for_each(|c| push("last_edited_time", c));
+            }
+        }
+        for (type_name, item) in [("id", &self.id), ("unique_id", &self.unique_id)] {
+            if let Some(item) = item {
+                id_conditions(item)
+                    .into_iter()
+                    .for_each(|c| push(type_name, c));
+            }
+        }
+        if let Some(item) = &self.rollup {
+            for (operator, item) in [
+                ("any", &item.any),
+                ("every", &item.every),
+                ("none", &item.none),
+            ] {
+                if let Some(item) = item {
+                    let condition = join_clauses(&item.clauses(true));
+                    push("rollup", format!("{operator} ({condition})"));
+                }
+            }
+            if let Some(item) = &item.number {
+                number_conditions(item)
+                    .into_iter()
+                    .for_each(|c| push("rollup.number", c));
+            }
+            if let Some(item) = &item.date {
+                date_conditions(item)
+                    .into_iter()
+                    .for_each(|c| push("rollup.date", c));
+            }
+        }
+        if let Some(status) = self
+            .verification
+            .as_ref()
+            .and_then(|item| item.status.as_ref())
+        {
+            push("verification", format!("= {}", quote(status)));
+        }
+        clauses
+    }
+}
+
+/// Join conditions with `and`, with parentheses around `or` groups
+fn join_clauses(clauses: &[(String, bool)]) -> String {
+    clauses
+        .iter()
+        .map(|(clause, is_or)| {
+            if *is_or && clauses.len() > 1 {
+                format!("({clause})")
+            } else {
+                clause.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" and ")
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Property names are written as is when they can be read back as a single word
+fn quote_property(property: &str) -> String {
+    let is_word = !property.is_empty()
+        && !property
+            .chars()
+            .any(|c| c.is_whitespace() || "\"():=!<>".contains(c))
+        && !KEYWORDS.contains(&property.to_lowercase().as_str());
+    if is_word {
+        property.to_string()
+    } else {
+        quote(property)
+    }
+}
+
+fn empty_conditions(is_empty: Option<bool>, is_not_empty: Option<bool>) -> Vec<String> {
+    let mut conditions = Vec::new();
+    if is_empty == Some(true) {
+        conditions.push(String::from("is empty"));
+    }
+    if is_not_empty == Some(true) {
+        conditions.push(String::from("is not empty"));
+    }
+    conditions
+}
+
+fn checkbox_conditions(item: &CheckboxFilterItem) -> Vec<String> {
+    item.equals
+        .map(|value| format!("= {value}"))
+        .into_iter()
+        .chain(item.does_not_equal.map(|value| format!("!= {value}")))
+        .collect()
+}
+
+fn date_conditions(item: &DateFilterItem) -> Vec<String> {
+    let mut conditions: Vec<String> = [
+        ("=", &item.equals),
+        ("<", &item.before),
+        (">", &item.after),
+        ("<=", &item.on_or_before),
+        (">=", &item.on_or_after),
+    ]
+    .into_iter()
+    .filter_map(|(operator, value)| Some(format!("{operator} {}", quote(value.as_ref()?))))
+    .collect();
+    conditions.extend(empty_conditions(item.is_empty, item.is_not_empty));
+    let relative = [
+        ("past_week", &item.past_week),
+        ("past_month", &item.past_month),
+        ("past_year", &item.past_year),
+        ("next_week", &item.next_week),
+        ("next_month", &item.next_month),
+        ("next_year", &item.next_year),
+        ("this_week", &item.this_week),
+    ];
+    conditions.extend(
+        relative
+            .into_iter()
+            .filter(|(_, value)| value.is_some())
+            .map(|(name, _)| format!("in {name}")),
+    );
+    conditions
+}
+
+fn number_conditions(item: &NumberFilterItem) -> Vec<String> {
+    let mut conditions: Vec<String> = [
+        ("=", &item.equals),
+        ("!=", &item.does_not_equal),
+        (">", &item.greater_than),
+        ("<", &item.less_than),
+        (">=", &item.greater_than_or_equal_to),
+        ("<=", &item.less_than_or_equal_to),
+    ]
+    .into_iter()
+    .filter_map(|(operator, value)| {
+        let value = match value.as_ref()? {
+            FilterNumber::Int(value) => value.to_string(),
+            // `{:?}` keeps the decimal point, so the value parses back as a float
+            FilterNumber::Float(value) => format!("{value:?}"),
+        };
+        Some(format!("{operator} {value}"))
+    })
+    .collect();
+    conditions.extend(empty_conditions(item.is_empty, item.is_not_empty));
+    conditions
+}
+
+fn rich_text_conditions(item: &RichTextFilterItem) -> Vec<String> {
+    let mut conditions: Vec<String> = [
+        ("=", &item.equals),
+        ("!=", &item.does_not_equal),
+        ("contains", &item.contains),
+        ("not contains", &item.does_not_contain),
+        ("starts with", &item.starts_with),
+        ("ends with", &item.ends_with),
+    ]
+    .into_iter()
+    .filter_map(|(operator, value)| Some(format!("{operator} {}", quote(value.as_ref()?))))
+    .collect();
+    conditions.extend(empty_conditions(item.is_empty, item.is_not_empty));
+    conditions
+}
+
+fn option_conditions(
+    equals: &Option<String>,
+    does_not_equal: &Option<String>,
+    is_empty: Option<bool>,
+    is_not_empty: Option<bool>,
+) -> Vec<String> {
+    let mut conditions: Vec<String> = [("=", equals), ("!=", does_not_equal)]
+        .into_iter()
+        .filter_map(|(operator, value)| Some(format!("{operator} {}", quote(value.as_ref()?))))
+        .collect();
+    conditions.extend(empty_conditions(is_empty, is_not_empty));
+    conditions
+}
+
+fn has_conditions(
+    contains: &Option<String>,
+    does_not_contain: &Option<String>,
+    is_empty: Option<bool>,
+    is_not_empty: Option<bool>,
+) -> Vec<String> {
+    let mut conditions: Vec<String> = [("has", contains), ("not has", does_not_contain)]
+        .into_iter()
+        .filter_map(|(operator, value)| Some(format!("{operator} {}", quote(value.as_ref()?))))
+        .collect();
+    conditions.extend(empty_conditions(is_empty, is_not_empty));
+    conditions
+}
+
+fn id_conditions(item: &IdFilterItem) -> Vec<String> {
+    [
+        ("=", item.equals),
+        ("!=", item.does_not_equal),
+        (">", item.greater_than),
+        ("<", item.less_than),
+        (">=", item.greater_than_or_equal_to),
+        ("<=", item.less_than_or_equal_to),
+    ]
+    .into_iter()
+    .filter_map(|(operator, value)| Some(format!("{operator} {}", value?)))
+    .collect()
+}
+
+/// Renders the filter in the query language read by [`FilterItem::parse`]
+impl fmt::Display for FilterItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&join_clauses(&self.clauses(false)))
+    }
+}
+
+impl FromStr for FilterItem {
+    type Err = FilterParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        FilterItem::parse(input)
+    }
+}
+
+impl QueryFilter {
+    /// Build a query filter from a filter expression. See [`FilterItem::parse`].
+    pub fn parse(input: &str) -> Result<Self, FilterParseError> {
+        let mut query_filter = QueryFilter::new();
+        query_filter.args(FilterItem::parse(input)?);
+        Ok(query_filter)
+    }
+
+    /// Build a query filter from a filter expression. See [`FilterItem::parse_with_schema`].
+    pub fn parse_with_schema(input: &str, database: &Database) -> Result<Self, FilterParseError> {
+        let mut query_filter = QueryFilter::new();
+        query_filter.args(FilterItem::parse_with_schema(input, database)?);
+        Ok(query_filter)
+    }
+}
+
+impl FromStr for QueryFilter {
+    type Err = FilterParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        QueryFilter::parse(input)
+    }
+}
@@ -1,9 +1,12 @@
 use super::common::*;
+use super::database::Database;
+use super::filter_dsl::{prop, timestamp};
 use super::page::*;
 use super::query_filter::FilterItem as DFItems;
 use super::query_filter::*;
 
 #[test]
+#[allow(deprecated)]
 fn test_build_query_filter() {
     let mut filter = QueryFilter::new();
     filter.or(vec![
@@ -32,6 +35,768 @@ fn test_build_query_filter() {
     );
 }
 
+#[test]
+fn test_build_checkbox_filter() {
+    let filter = DFItems::or(vec![
+        DFItems::checkbox(String::from("Done"), CheckboxFilterItem::is(true)),
+        DFItems::checkbox(String::from("Done"), CheckboxFilterItem::is(false)),
+        DFItems::checkbox(String::from("Done"), CheckboxFilterItem::is_not(true)),
+        DFItems::formula(
+            String::from("Overdue"),
+            FormulaFilterItem::checkbox(CheckboxFilterItem::is_not(false)),
+        ),
+    ]);
+    assert_eq!(
+        serde_json::to_string(&filter).unwrap(),
+        r#"{"or":[{"property":"Done","checkbox":{"equals":true}},{"property":"Done","checkbox":{"equals":false}},{"property":"Done","checkbox":{"does_not_equal":true}},{"property":"Overdue","formula":{"checkbox":{"does_not_equal":false}}}]}"#,
+    );
+
+    // the deprecated constructors keep comparing with `true`
+    #[allow(deprecated)]
+    let item = CheckboxFilterItem::does_not_equal();
+    assert_eq!(item.does_not_equal, Some(true));
+}
+
+#[test]
+fn test_build_rollup_and_people_filters() {
+    let filter = DFItems::and(vec![
+        DFItems::rollup(
+            String::from("Tasks"),
+            RollupFilterItem::any(DFItems::rich_text(
+                String::from("ignored"),
+                RichTextFilterItem::contains(String::from("Urgent")),
+            )),
+        ),
+        DFItems::rollup(
+            String::from("Estimate"),
+            RollupFilterItem::number(NumberFilterItem::greater_than(8)),
+        ),
+        DFItems::created_by(
+            String::from("Created by"),
+            PeopleFilterItem::contains(String::from("c2f20311-9e54-4d11-8c79-7398424ae41e")),
+        ),
+        DFItems::last_edited_by(
+            String::from("Last edited by"),
+            PeopleFilterItem::is_not_empty(),
+        ),
+        DFItems::verification(
+            String::from("Verification"),
+            VerificationFilterItem::verified(),
+        ),
+        DFItems::unique_id(String::from("ID"), IdFilterItem::greater_than(42)),
+    ]);
+    assert_eq!(
+        serde_json::to_string(&filter).unwrap(),
+        r#"{"and":[{"property":"Tasks","rollup":{"any":{"rich_text":{"contains":"Urgent"}}}},{"property":"Estimate","rollup":{"number":{"greater_than":8}}},{"property":"Created by","created_by":{"contains":"c2f20311-9e54-4d11-8c79-7398424ae41e"}},{"property":"Last edited by","last_edited_by":{"is_not_empty":true}},{"property":"Verification","verification":{"status":"verified"}},{"property":"ID","unique_id":{"greater_than":42}}]}"#,
+    );
+    assert!(!DFItems::rollup(String::new(), RollupFilterItem::default()).is_empty());
+}
+
+#[test]
+fn test_build_filter_dsl() {
+    let filter = prop("Status").status().eq("Active")
+        & (prop("Age").number().gt(18) | prop("Address").text().contains("NY"));
+    let expected = DFItems::and(vec![
+        DFItems::status(
+            String::from("Status"),
+            StatusFilterItem::equals(String::from("Active")),
+        ),
+        DFItems::or(vec![
+            DFItems::number(String::from("Age"), NumberFilterItem::greater_than(18)),
+            DFItems::rich_text(
+                String::from("Address"),
+                RichTextFilterItem::contains(String::from("NY")),
+            ),
+        ]),
+    ]);
+    assert_eq!(
+        serde_json::to_string(&filter).unwrap(),
+        serde_json::to_string(&expected).unwrap(),
+    );
+
+    // chained operators are flattened into one group
+    let filter = prop("Done").checkbox().eq(false)
+        & prop("Due").date().past_week()
+        & prop("Score").formula().number().gte(2.5)
+        & (prop("Tags").multi_select().contains("a") | prop("Tags").multi_select().is_empty());
+    assert_eq!(
+        serde_json::to_string(&filter).unwrap(),
+        r#"{"and":[{"property":"Done","checkbox":{"equals":false}},{"property":"Due","date":{"past_week":{}}},{"property":"Score","formula":{"number":{"greater_than_or_equal_to":2.5}}},{"or":[{"property":"Tags","multi_select":{"contains":"a"}},{"property":"Tags","multi_select":{"is_empty":true}}]}]}"#,
+    );
+
+    let filter = prop("Tasks").rollup().every(prop("").status().eq("Done"))
+        | prop("Owner").created_by().is_empty();
+    assert_eq!(
+        serde_json::to_string(&filter).unwrap(),
+        r#"{"or":[{"property":"Tasks","rollup":{"every":{"status":{"equals":"Done"}}}},{"property":"Owner","created_by":{"is_empty":true}}]}"#,
+    );
+
+    // empty operands are dropped, so filters can be folded from an empty item
+    let filter = DFItems::default() & prop("A").number().gt(1);
+    assert_eq!(
+        serde_json::to_string(&filter).unwrap(),
+        r#"{"property":"A","number":{"greater_than":1}}"#,
+    );
+    let filter = [1, 2, 3].into_iter().fold(DFItems::default(), |filter, i| {
+        filter | prop("A").number().eq(i)
+    });
+    assert_eq!(
+        serde_json::to_string(&filter).unwrap(),
+        r#"{"or":[{"property":"A","number":{"equals":1}},{"property":"A","number":{"equals":2}},{"property":"A","number":{"equals":3}}]}"#,
+    );
+    assert!((DFItems::default() & DFItems::default()).is_empty());
+
+    let filter = timestamp().created_time().past_week()
+        & timestamp().last_edited_time().after("2024-05-01")
+        & prop("ID").id().gt(5)
+        & prop("Ticket").unique_id().lte(10);
+    let expected = DFItems::and(vec![
+        DFItems::timestamp(
+            String::new(),
+            TimestampFilterItem::created_time(DateFilterItem::past_week()),
+        ),
+        DFItems::timestamp(
+            String::new(),
+            TimestampFilterItem::last_edited_time(DateFilterItem::after("2024-05-01")),
+        ),
+        DFItems::id(String::from("ID"), IdFilterItem::greater_than(5)),
+        DFItems::unique_id(
+            String::from("Ticket"),
+            IdFilterItem::less_than_or_equal_to(10),
+        ),
+    ]);
+    assert_eq!(
+        serde_json::to_string(&filter).unwrap(),
+        serde_json::to_string(&expected).unwrap(),
+    );
+}
+
+#[test]
+fn test_validate_query_filter() {
+    let database: Database = serde_json::from_str(
+        r#"{
+            "object": "database",
+            "id": "d9824bdc-8445-4327-be8b-5b47500af6ce",
+            "properties": {
+                "Name": {"id": "title", "name": "Name", "type": "title", "title": {}},
+                "Status": {"id": "%3AUPp", "name": "Status", "type": "status", "status": {"options": []}},
+                "Age": {"id": "a%3Dx", "name": "Age", "type": "number", "number": {"format": "number"}},
+                "Owner": {"id": "pplA", "name": "Owner", "type": "created_by", "created_by": {}},
+                "Tasks": {"id": "tsk", "name": "Tasks", "type": "rollup", "rollup": {"rollup_property_id": "st", "function": "show_original"}}
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let mut filter = QueryFilter::new();
+    filter.args(
+        prop("Status").status().eq("Active")
+            & prop("%3AUPp").status().is_not_empty()
+            & (prop("Name").text().contains("Zack") | prop("Owner").people().is_empty()),
+    );
+    filter.sort(Sort::descending(String::from("a%3Dx")));
+    assert!(filter.validate(&database).is_ok());
+
+    let mut filter = QueryFilter::new();
+    filter.and(vec![
+        prop("Missing").number().gt(1),
+        prop("Status").select().eq("Active"),
+        prop("Age").number().gt(18)
+            | (prop("Name").text().is_empty()
+                & (prop("Age").number().lt(3) | prop("Age").number().gt(99))),
+        DFItems::date(String::new(), DateFilterItem::past_week()),
+    ]);
+    filter.sort(Sort::ascending(String::from("Priority")));
+    assert_eq!(
+        filter.validate(&database).unwrap_err(),
+        vec![
+            FilterValidationError::UnknownProperty {
+                path: String::from("filter.and[0]"),
+                property: String::from("Missing"),
+            },
+            FilterValidationError::IncompatibleType {
+                path: String::from("filter.and[1]"),
+                property: String::from("Status"),
+                filter: String::from("select"),
+                property_type: String::from("status"),
+            },
+            FilterValidationError::TooDeeplyNested {
+                path: String::from("filter.and[2].or[1].and"),
+            },
+            FilterValidationError::MissingProperty {
+                path: String::from("filter.and[3]"),
+                filter: String::from("date"),
+            },
+            FilterValidationError::UnknownProperty {
+                path: String::from("sorts[0]"),
+                property: String::from("Priority"),
+            },
+        ]
+    );
+
+    let mut filter = QueryFilter::new();
+    filter.args(prop("Age").number().gt(f64::NAN) | prop("Age").number().lt(f32::INFINITY));
+    assert_eq!(
+        filter.validate(&database).unwrap_err(),
+        vec![
+            FilterValidationError::NonFiniteNumber {
+                path: String::from("filter.or[0]"),
+            },
+            FilterValidationError::NonFiniteNumber {
+                path: String::from("filter.or[1]"),
+            },
+        ]
+    );
+
+    let mut filter = QueryFilter::new();
+    filter.and(vec![
+        prop("Tasks").rollup().any(prop("").status().eq("Done")),
+        prop("Tasks")
+            .rollup()
+            .every(prop("").number().gt(1) | prop("").number().lt(0)),
+        prop("Tasks")
+            .rollup()
+            .none(prop("").formula().number().gt(f64::INFINITY)),
+    ]);
+    assert_eq!(
+        filter.validate(&database).unwrap_err(),
+        vec![
+            FilterValidationError::InvalidRollupCondition {
+                path: String::from("filter.and[1].rollup.every"),
+                filter: String::from("or"),
+            },
+            FilterValidationError::InvalidRollupCondition {
+                path: String::from("filter.and[2].rollup.none"),
+                filter: String::from("formula"),
+            },
+            FilterValidationError::NonFiniteNumber {
+                path: String::from("filter.and[2].rollup.none"),
+            },
+        ]
+    );
+}
+
+fn sample_page() -> Page {
+    serde_json::from_str(
+        r#"{
+            "object": "page",
+            "id": "59833787-2cf9-4fdf-8782-e53db20768a5",
+            "created_time": "2024-05-01T09:30:00.000Z",
+            "last_edited_time": "2024-05-10T18:00:00.000Z",
+            "properties": {
+                "Name": {"id": "title", "type": "title", "title": [
+                    {"type": "text", "text": {"content": "Quarterly "}, "plain_text": "Quarterly "},
+                    {"type": "text", "text": {"content": "Report"}, "plain_text": "Report"}
+                ]},
+                "Notes": {"id": "n0te", "type": "rich_text", "rich_text": []},
+                "Done": {"id": "d0ne", "type": "checkbox", "checkbox": false},
+                "Age": {"id": "a%3Dx", "type": "number", "number": 42},
+                "Budget": {"id": "bdgt", "type": "number", "number": null},
+                "Due": {"id": "due0", "type": "date", "date": {"start": "2024-05-08", "end": null}},
+                "Meeting": {"id": "mtng", "type": "date", "date": {"start": "2024-05-08T15:00:00.000+09:00"}},
+                "Tags": {"id": "tags", "type": "multi_select", "multi_select": [
+                    {"id": "1", "name": "Finance", "color": "blue"},
+                    {"id": "2", "name": "Q2", "color": "red"}
+                ]},
+                "Status": {"id": "%3AUPp", "type": "status", "status": {"id": "s", "name": "In progress", "color": "blue"}},
+                "Priority": {"id": "prio", "type": "select", "select": null},
+                "Owner": {"id": "ownr", "type": "people", "people": [
+                    {"object": "user", "id": "c2f20311-9e54-4d11-8c79-7398424ae41e"}
+                ]},
+                "Created by": {"id": "crby", "type": "created_by", "created_by": {"object": "user", "id": "6794760a-1f15-45cd-9c65-0dfe42f5135a"}},
+                "Projects": {"id": "proj", "type": "relation", "relation": [{"id": "dd456007-6c66-4bba-957e-ea501dcda3a6"}], "has_more": false},
+                "Attachments": {"id": "file", "type": "files", "files": []},
+                "Score": {"id": "scor", "type": "formula", "formula": {"type": "number", "number": 7.5}},
+                "Tasks": {"id": "task", "type": "rollup", "rollup": {"type": "array", "function": "show_original", "array": [
+                    {"type": "status", "status": {"name": "Done"}},
+                    {"type": "status", "status": {"name": "In progress"}}
+                ]}},
+                "ID": {"id": "uid0", "type": "unique_id", "unique_id": {"number": 17, "prefix": "TASK"}},
+                "Website": {"id": "url0", "type": "url", "url": "https://example.com/Report"}
+            }
+        }"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_filter_matches_page() {
+    let page = sample_page();
+    let today = "2024-05-12";
+    let matches = |filter: DFItems| filter.matches_on(&page, today);
+
+    // text
+    assert!(matches(prop("Name").text().contains("quarterly rep")));
+    assert!(matches(prop("Name").text().starts_with("QUARTER")));
+    assert!(matches(prop("Name").text().ends_with("report")));
+    assert!(matches(prop("Name").text().eq("Quarterly Report")));
+    assert!(!matches(prop("Name").text().eq("quarterly report")));
+    assert!(matches(prop("Name").text().does_not_contain("Annual")));
+    assert!(matches(prop("Notes").text().is_empty()));
+    assert!(matches(prop("Notes").text().does_not_contain("x")));
+    assert!(matches(prop("Website").text().contains("example.com")));
+    assert!(!matches(prop("Missing").text().is_empty()));
+
+    // numbers, checkboxes and formulas
+    assert!(matches(
+        prop("Age").number().gt(18) & prop("Age").number().lte(42)
+    ));
+    assert!(!matches(prop("Age").number().lt(42)));
+    assert!(matches(prop("a%3Dx").number().eq(42.0)));
+    assert!(matches(prop("Budget").number().is_empty()));
+    assert!(matches(prop("Budget").number().ne(3)));
+    assert!(!matches(prop("Budget").number().gt(0)));
+    assert!(matches(prop("Done").checkbox().eq(false)));
+    assert!(!matches(prop("Done").checkbox().ne(false)));
+    assert!(matches(prop("Score").formula().number().gte(7.5)));
+    assert!(!matches(prop("Score").formula().text().eq("7.5")));
+
+    // dates
+    assert!(matches(prop("Due").date().eq("2024-05-08")));
+    assert!(matches(prop("Due").date().eq("2024-05-08T23:00:00Z")));
+    assert!(matches(
+        prop("Due").date().after("2024-05-07") & prop("Due").date().before("2024-05-09")
+    ));
+    assert!(matches(prop("Due").date().on_or_before("2024-05-08")));
+    assert!(!matches(prop("Due").date().after("2024-05-08")));
+    assert!(matches(
+        prop("Meeting").date().before("2024-05-08T07:00:00Z")
+    ));
+    assert!(matches(
+        prop("Meeting").date().after("2024-05-08T05:59:59Z")
+    ));
+    assert!(matches(prop("Due").date().past_week()));
+    assert!(matches(
+        prop("Due").date().past_month() & prop("Due").date().past_year()
+    ));
+    assert!(!matches(prop("Due").date().next_week()));
+    assert!(matches(prop("Due").date().this_week()));
+    assert!(!prop("Due")
+        .date()
+        .this_week()
+        .matches_on(&page, "2024-05-13"));
+    assert!(prop("Due")
+        .date()
+        .next_month()
+        .matches_on(&page, "2024-04-08"));
+    assert!(!prop("Due")
+        .date()
+        .next_month()
+        .matches_on(&page, "2024-04-07"));
+    assert!(matches(DFItems::timestamp(
+        String::new(),
+        TimestampFilterItem::created_time(DateFilterItem::on_or_after(String::from("2024-05-01"))),
+    )));
+    assert!(matches(
+        prop("Priority").select().is_empty() | prop("Priority").select().eq("High")
+    ));
+
+    // options, people and relations
+    assert!(matches(prop("Tags").multi_select().contains("Q2")));
+    assert!(!matches(prop("Tags").multi_select().contains("q2")));
+    assert!(matches(prop("Tags").multi_select().does_not_contain("Q3")));
+    assert!(matches(prop("Status").status().eq("In progress")));
+    assert!(matches(prop("%3AUPp").status().ne("Done")));
+    assert!(matches(prop("Priority").select().ne("High")));
+    assert!(matches(
+        prop("Owner")
+            .people()
+            .contains("c2f203119e544d118c797398424ae41e")
+    ));
+    assert!(matches(
+        prop("Created by")
+            .created_by()
+            .contains("6794760a-1f15-45cd-9c65-0dfe42f5135a")
+    ));
+    assert!(matches(prop("Created by").people().is_not_empty()));
+    assert!(matches(
+        prop("Projects")
+            .relation()
+            .contains("dd456007-6c66-4bba-957e-ea501dcda3a6")
+    ));
+    assert!(matches(prop("Attachments").files().is_empty()));
+    assert!(matches(
+        prop("ID").unique_id().gt(16) & prop("ID").unique_id().lt(18)
+    ));
+
+    // rollups
+    assert!(matches(
+        prop("Tasks").rollup().any(prop("").status().eq("Done"))
+    ));
+    assert!(!matches(
+        prop("Tasks").rollup().every(prop("").status().eq("Done"))
+    ));
+    assert!(matches(
+        prop("Tasks").rollup().none(prop("").status().eq("Blocked"))
+    ));
+
+    // compound filters
+    let mut filter = QueryFilter::new();
+    assert!(filter.matches(&page));
+    filter.args(
+        prop("Status").status().eq("Done")
+            | (prop("Tags").multi_select().contains("Finance") & prop("Age").number().gt(40)),
+    );
+    assert!(filter.matches(&page));
+    filter.args(prop("Status").status().eq("Done") | prop("Age").number().gt(50));
+    assert!(!filter.matches(&page));
+}
+
+#[test]
+fn test_parse_query_filter() {
+    let filter =
+        QueryFilter::parse(r#"Status:status = "ToDo" and (Priority >= 2 or Tags has "urgent")"#)
+            .unwrap();
+    let expected = prop("Status").status().eq("ToDo")
+        & (prop("Priority").number().gte(2) | prop("Tags").multi_select().contains("urgent"));
+    assert_eq!(
+        serde_json::to_string(&filter.filter).unwrap(),
+        serde_json::to_string(&expected).unwrap(),
+    );
+
+    // property types are taken from the schema when they are not written
+    let database: Database = serde_json::from_str(
+        r#"{"properties": {
+            "Status": {"id": "s", "type": "status", "status": {"options": []}},
+            "Name": {"id": "title", "type": "title", "title": {}},
+            "Score": {"id": "f", "type": "formula", "formula": {"expression": "1"}}
+        }}"#,
+    )
+    .unwrap();
+    let filter = QueryFilter::parse_with_schema(
+        r#"Status = "ToDo" and Name = Report and Score > 1.5"#,
+        &database,
+    )
+    .unwrap();
+    assert_eq!(
+        filter.build(),
+        r#"{"filter":{"and":[{"property":"Status","status":{"equals":"ToDo"}},{"property":"Name","rich_text":{"equals":"Report"}},{"property":"Score","formula":{"number":{"greater_than":1.5}}}]}}"#,
+    );
+
+    // without a schema, text equality defaults to rich_text
+    let filter =
+        QueryFilter::parse(r#"Status = "ToDo" and (Priority >= 2 or Tags has "urgent")"#).unwrap();
+    let expected = prop("Status").text().eq("ToDo")
+        & (prop("Priority").number().gte(2) | prop("Tags").multi_select().contains("urgent"));
+    assert_eq!(
+        serde_json::to_string(&filter.filter).unwrap(),
+        serde_json::to_string(&expected).unwrap(),
+    );
+
+    // unquoted dates and date-times are single values
+    for (text, expected) in [
+        (
+            "Due:date > 2024-01-01T10:00:00Z",
+            prop("Due").date().after("2024-01-01T10:00:00Z"),
+        ),
+        (
+            "Due <= 2024-01-01T10:00:00.000+09:00",
+            prop("Due")
+                .date()
+                .on_or_before("2024-01-01T10:00:00.000+09:00"),
+        ),
+        ("Due:date = 2024-01-01", prop("Due").date().eq("2024-01-01")),
+    ] {
+        assert_eq!(
+            serde_json::to_string(&QueryFilter::parse(text).unwrap().filter).unwrap(),
+            serde_json::to_string(&expected).unwrap(),
+            "{}",
+            text,
+        );
+    }
+
+    let error = QueryFilter::parse("Tags is empty").unwrap_err();
+    assert_eq!(error.position, 0);
+    assert!(error.message.contains("Tags:<type>"));
+    assert_eq!(
+        QueryFilter::parse(r#"(Done = true"#).unwrap_err(),
+        FilterParseError {
+            position: 12,
+            message: String::from("expected `)`"),
+        }
+    );
+    assert_eq!(
+        QueryFilter::parse(r#"Done:checkbox contains "x""#)
+            .unwrap_err()
+            .message,
+        "operator not supported by `checkbox` filters",
+    );
+    assert!(QueryFilter::parse("").unwrap().filter.is_empty());
+
+    // numbers without a JSON representation are rejected
+    for text in [
+        "Price > -inf",
+        "Price > 1e400",
+        "Price:number > inf",
+        "Price:number = NaN",
+    ] {
+        assert!(QueryFilter::parse(text).is_err(), "{}", text);
+    }
+    assert_eq!(
+        QueryFilter::parse("Price > 1e400").unwrap_err(),
+        FilterParseError {
+            position: 8,
+            message: String::from("1e400 is not a finite number"),
+        }
+    );
+}
+
+#[test]
+fn test_display_filter_item() {
+    let filter = prop("Status").status().eq("ToDo")
+        & (prop("Priority").number().gte(2) | prop("Tags").multi_select().contains("urgent"))
+        & prop("Due date").date().past_week()
+        & prop("Name").text().does_not_contain(r#"say "hi""#)
+        & prop("Tasks").rollup().any(prop("").status().eq("Done"))
+        & prop("and").checkbox().eq(true)
+        & DFItems::timestamp(
+            String::new(),
+            TimestampFilterItem::created_time(DateFilterItem::on_or_after(String::from(
+                "2024-05-01T09:30:00Z",
+            ))),
+        );
+    let text = filter.to_string();
+    assert_eq!(
+        text,
+        r#"Status:status = "ToDo" and (Priority:number >= 2 or Tags:multi_select has "urgent") and "Due date":date in past_week and Name:rich_text not contains "say \"hi\"" and Tasks:rollup any (status = "Done") and "and":checkbox = true and "":created_time >= "2024-05-01T09:30:00Z""#,
+    );
+    let parsed: DFItems = text.parse().unwrap();
+    assert_eq!(
+        serde_json::to_string(&parsed).unwrap(),
+        serde_json::to_string(&filter).unwrap(),
+    );
+
+    // the text parses back to the same filter
+    let round_trip = |filter: DFItems| {
+        let parsed: DFItems = filter.to_string().parse().unwrap();
+        assert_eq!(
+            serde_json::to_string(&parsed).unwrap(),
+            serde_json::to_string(&filter).unwrap(),
+            "{}",
+            filter,
+        );
+    };
+    let filter = prop("P").number().gt(2.0);
+    assert_eq!(filter.to_string(), "P:number > 2.0");
+    round_trip(filter);
+    round_trip(prop("P").number().lte(-0.5) | prop("P").number().eq(1e20));
+    round_trip(prop("Score").formula().number().eq(3.0));
+    round_trip(
+        prop("Tasks")
+            .rollup()
+            .every(prop("").number().gte(1.0) & prop("").number().lt(5)),
+    );
+    round_trip(
+        prop("Tasks")
+            .rollup()
+            .none(prop("").text().contains("x") | prop("").date().is_empty()),
+    );
+    // the property of a rollup condition is not sent to the API and not written
+    let filter = prop("Tasks")
+        .rollup()
+        .any(prop("Status").status().eq("Done"));
+    assert_eq!(filter.to_string(), r#"Tasks:rollup any (status = "Done")"#);
+    assert_eq!(
+        FilterItem::parse(r#"Tasks:rollup any ("":status = "Done")"#)
+            .unwrap()
+            .to_string(),
+        r#"Tasks:rollup any (status = "Done")"#,
+    );
+
+    // empty items are skipped, so the text parses back
+    let filter = DFItems::or(vec![DFItems::default(), prop("B").number().eq(2)]);
+    assert_eq!(filter.to_string(), "B:number = 2");
+    assert!(filter.to_string().parse::<DFItems>().is_ok());
+    let filter = DFItems::or(vec![
+        DFItems::default(),
+        prop("A").number().eq(1),
+        DFItems::and(vec![DFItems::default()]),
+        prop("B").number().eq(2),
+    ]);
+    assert_eq!(filter.to_string(), "A:number = 1 or B:number = 2");
+    assert_eq!(DFItems::or(vec![DFItems::default()]).to_string(), "");
+
+    let filter =
+        (prop("A").number().eq(1) & prop("B").number().eq(2.5)) | prop("C").files().is_empty();
+    assert_eq!(
+        filter.to_string(),
+        "(A:number = 1 and B:number = 2.5) or C:files is empty"
+    );
+}
+
+#[test]
+fn test_build_query_filter_with_sorts() {
+    let mut filter = QueryFilter::new();
+    filter.args(DFItems::status(
+        String::from("Status"),
+        StatusFilterItem::equals(String::from("ToDo")),
+    ));
+    filter.sort(Sort::descending(String::from("Priority")));
+    filter.sort(Sort::ascending(String::from("Name")));
+    filter.sort(Sort::last_edited_time(SortDirection::Descending));
+
+    assert_eq!(
+        filter.build(),
+        r#"{"filter":{"property":"Status","status":{"equals":"ToDo"}},"sorts":[{"property":"Priority","direction":"descending"},{"property":"Name","direction":"ascending"},{"timestamp":"last_edited_time","direction":"descending"}]}"#,
+    );
+
+    // sorts without a filter
+    let mut filter = QueryFilter::new();
+    filter.sort(Sort::created_time(SortDirection::Descending));
+    assert_eq!(
+        filter.build(),
+        r#"{"sorts":[{"timestamp":"created_time","direction":"descending"}]}"#,
+    );
+    assert_eq!(QueryFilter::new().build(), "{}");
+}
+
+#[test]
+fn test_build_number_filter() {
+    let filter = DFItems::and(vec![
+        DFItems::number(String::from("Price"), NumberFilterItem::greater_than(9.99)),
+        DFItems::number(String::from("Delta"), NumberFilterItem::less_than(-5)),
+        DFItems::number(String::from("Count"), NumberFilterItem::equals(3)),
+        DFItems::formula(
+            String::from("Ratio"),
+            FormulaFilterItem::number(NumberFilterItem::greater_than_or_equal_to(0.5_f32)),
+        ),
+        DFItems::id(String::from("ID"), IdFilterItem::less_than(100)),
+    ]);
+    assert_eq!(
+        serde_json::to_string(&filter).unwrap(),
+        r#"{"and":[{"property":"Price","number":{"greater_than":9.99}},{"property":"Delta","number":{"less_than":-5}},{"property":"Count","number":{"equals":3}},{"property":"Ratio","formula":{"number":{"greater_than_or_equal_to":0.5}}},{"property":"ID","id":{"less_than":100}}]}"#,
+    );
+
+    let item =
+        serde_json::from_str::<NumberFilterItem>(r#"{"equals":-1.5,"does_not_equal":7}"#).unwrap();
+    assert_eq!(item.equals, Some(FilterNumber::Float(-1.5)));
+    assert_eq!(item.does_not_equal, Some(FilterNumber::Int(7)));
+    assert_eq!(item.does_not_equal.unwrap().as_f64(), 7.0);
+
+    assert_eq!(
+        serde_json::to_string(&NumberFilterItem::greater_than(9.99_f32)).unwrap(),
+        r#"{"greater_than":9.99}"#,
+    );
+    assert_eq!(FilterNumber::from(42_u128), FilterNumber::Int(42));
+    assert_eq!(FilterNumber::from(42_usize), FilterNumber::Int(42));
+    assert_eq!(
+        FilterNumber::from(u64::MAX),
+        FilterNumber::Float(u64::MAX as f64)
+    );
+}
+
+#[test]
+fn test_build_relative_date_filter() {
+    let filter = DFItems::or(vec![
+        DFItems::date(String::from("Due"), DateFilterItem::past_week()),
+        DFItems::date(String::from("Due"), DateFilterItem::this_week()),
+        DFItems::date(String::from("Due"), DateFilterItem::next_year()),
+        DFItems::timestamp(
+            String::from("Edited"),
+            TimestampFilterItem::last_edited_time(DateFilterItem::past_month()),
+        ),
+        DFItems::formula(
+            String::from("Review"),
+            FormulaFilterItem::date(DateFilterItem::next_month()),
+        ),
+    ]);
+    assert_eq!(
+        serde_json::to_string(&filter).unwrap(),
+        r#"{"or":[{"property":"Due","date":{"past_week":{}}},{"property":"Due","date":{"this_week":{}}},{"property":"Due","date":{"next_year":{}}},{"property":"Edited","timestamp":{"last_edited_time":{"past_month":{}}}},{"property":"Review","formula":{"date":{"next_month":{}}}}]}"#,
+    );
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_chrono_dates() {
+    use crate::error::NotionError;
+    use chrono::{FixedOffset, NaiveDate, TimeZone, Utc};
+
+    let date = NaiveDate::from_ymd_opt(2024, 12, 20).unwrap();
+    let item = DateFilterItem::on_or_after(date);
+    assert_eq!(item.on_or_after.as_deref(), Some("2024-12-20"));
+
+    let datetime = FixedOffset::east_opt(9 * 3600)
+        .unwrap()
+        .with_ymd_and_hms(2024, 12, 20, 10, 0, 0)
+        .unwrap();
+    let item = DateFilterItem::before(datetime);
+    assert_eq!(
+        item.before.as_deref(),
+        Some("2024-12-20T10:00:00.000+09:00")
+    );
+
+    let prop = PageProperty::date(Utc.with_ymd_and_hms(2024, 12, 20, 1, 0, 0).unwrap());
+    assert_eq!(prop.get_value(), "2024-12-20T01:00:00.000Z");
+    let prop = PageProperty::date(date.and_hms_opt(10, 30, 0).unwrap());
+    assert_eq!(prop.get_value(), "2024-12-20T10:30:00.000");
+
+    // malformed dates are rejected before sending
+    let mut filter = QueryFilter::new();
+    filter.and(vec![
+        DFItems::date(String::from("Due"), DateFilterItem::after(date)),
+        DFItems::or(vec![DFItems::formula(
+            String::from("Review"),
+            FormulaFilterItem::date(DateFilterItem::equals("2024-13-45")),
+        )]),
+    ]);
+    match filter.validate_dates() {
+        Err(NotionError::InvalidDate(value)) => assert_eq!(value, "2024-13-45"),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    filter.and(vec![DFItems::date(
+        String::from("Due"),
+        DateFilterItem::on_or_before("2024-12-20T10:00:00Z"),
+    )]);
+    assert!(filter.validate_dates().is_ok());
+
+    let mut properties = fxhash::FxHashMap::default();
+    properties.insert(String::from("Due"), PageProperty::date("tomorrow"));
+    let mut page = Page::from_properties(properties);
+    assert!(page.validate_dates().is_err());
+
+    page.created_time = String::from("2024-12-20T10:00:00.000+09:00");
+    assert_eq!(
+        page.created_datetime(),
+        Some(Utc.with_ymd_and_hms(2024, 12, 20, 1, 0, 0).unwrap())
+    );
+    assert_eq!(page.last_edited_datetime(), None);
+}
+
+#[test]
+fn test_date_range() {
+    let prop = serde_json::from_str::<PageProperty>(
+        r#"{"id":"a%3Ab","type":"date","date":{"start":"2024-01-01","end":"2024-01-05","time_zone":null}}"#,
+    )
+    .unwrap();
+    let date = prop.date.clone().unwrap();
+    assert_eq!(date.start, "2024-01-01");
+    assert_eq!(date.end.as_deref(), Some("2024-01-05"));
+    assert_eq!(date.time_zone, None);
+    assert_eq!(prop.get_value(), "2024-01-01 → 2024-01-05");
+
+    let prop = PageProperty::date_range("2024-01-01T09:00:00", "2024-01-01T18:00:00");
+    assert_eq!(
+        serde_json::to_string(&prop).unwrap(),
+        r#"{"date":{"start":"2024-01-01T09:00:00","end":"2024-01-01T18:00:00"}}"#,
+    );
+    let prop = PageProperty::zoned_date("2024-01-01T09:00:00", "Asia/Tokyo");
+    assert_eq!(
+        serde_json::to_string(&prop).unwrap(),
+        r#"{"date":{"start":"2024-01-01T09:00:00","time_zone":"Asia/Tokyo"}}"#,
+    );
+    let prop =
+        PageProperty::zoned_date_range("2024-01-01T09:00:00", "2024-01-01T18:00:00", "Asia/Tokyo");
+    assert_eq!(
+        serde_json::to_string(&prop).unwrap(),
+        r#"{"date":{"start":"2024-01-01T09:00:00","end":"2024-01-01T18:00:00","time_zone":"Asia/Tokyo"}}"#,
+    );
+    assert_eq!(
+        prop.get_value(),
+        "2024-01-01T09:00:00 → 2024-01-01T18:00:00 (Asia/Tokyo)"
+    );
+}
+
 #[test]
 fn test_page_property_get_value() {
     let porp = PageProperty::checkbox(true);
@@ -40,6 +805,12 @@ fn test_page_property_get_value() {
     let porp = PageProperty::date(String::from("2024-12-20"));
     assert_eq!(porp.get_value(), "2024-12-20");
 
+    let porp = PageProperty::date_range(String::from("2024-01-01"), String::from("2024-01-05"));
+    assert_eq!(porp.get_value(), "2024-01-01 → 2024-01-05");
+
+    let porp = PageProperty::zoned_date("2024-01-01T10:00:00", String::from("Asia/Tokyo"));
+    assert_eq!(porp.get_value(), "2024-01-01T10:00:00 (Asia/Tokyo)");
+
     let porp = PageProperty::number(123.456);
     assert_eq!(porp.get_value(), "123.456");
 
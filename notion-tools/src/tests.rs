@@ -1,10 +1,1011 @@
 use super::*;
+use crate::error::ErrorCode;
+use crate::rate_limit::RateLimiter;
+use crate::retry::RetryPolicy;
 use fxhash::FxHashMap;
+use serde_json::json;
+use std::time::Duration;
+use wiremock::matchers::{
+    body_json, body_partial_json, header, method, path, path_regex, query_param,
+};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn mock_notion(server: &MockServer) -> Notion {
+    Notion::builder()
+        .api_key(String::from("test-api-key"))
+        .base_url(format!("{}/v1", server.uri()))
+        .retry_policy(RetryPolicy {
+            base_delay: Duration::from_millis(1),
+            ..Default::default()
+        })
+        .build()
+}
+
+#[tokio::test]
+async fn test_base_url() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/databases/test-database-id"))
+        .and(header("Authorization", "Bearer test-api-key"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "object": "database",
+            "id": "test-database-id",
+            "properties": {
+                "Name": {"id": "title", "name": "Name", "type": "title", "title": {}}
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let notion = mock_notion(&server);
+    let database = notion
+        .retrieve_a_database(String::from("test-database-id"))
+        .await
+        .unwrap();
+    assert_eq!(database.id, "test-database-id");
+    assert_eq!(database.status, 200);
+    assert!(database.properties.contains_key("Name"));
+
+    let notion = Notion::builder()
+        .base_url(String::from("http://localhost:8080/v1/"))
+        .build();
+    assert_eq!(notion.base_url(), "http://localhost:8080/v1");
+    assert_eq!(Notion::builder().build().base_url(), NOTION_API_BASE_URL);
+}
+
+#[test]
+fn test_with_token() {
+    let notion = Notion::with_token(String::from("test-api-key"));
+    assert_eq!(notion.api_key, "test-api-key");
+    assert_eq!(notion.base_url(), NOTION_API_BASE_URL);
+
+    let error = NotionError::MissingEnvVar(String::from("NOTION_API_KEY"));
+    assert_eq!(
+        error.to_string(),
+        "Environment variable NOTION_API_KEY is not set"
+    );
+}
+
+#[tokio::test]
+async fn test_custom_client() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/pages/test-page-id"))
+        .and(header("User-Agent", "notion-tools-test"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "object": "page",
+            "id": "test-page-id"
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = reqwest::Client::builder()
+        .user_agent("notion-tools-test")
+        .timeout(std::time::Duration::from_secs(5))
+        .build()
+        .unwrap();
+    let notion = Notion::builder()
+        .api_key(String::from("test-api-key"))
+        .base_url(format!("{}/v1", server.uri()))
+        .client(client)
+        .build();
+
+    let page = notion
+        .retrieve_a_page(String::from("test-page-id"))
+        .await
+        .unwrap();
+    assert_eq!(page.id, "test-page-id");
+
+    // clones share the same client
+    let page = notion
+        .clone()
+        .retrieve_a_page(String::from("test-page-id"))
+        .await
+        .unwrap();
+    assert_eq!(page.id, "test-page-id");
+}
+
+#[tokio::test]
+async fn test_api_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/pages/missing-page-id"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+            "object": "error",
+            "status": 404,
+            "code": "object_not_found",
+            "message": "Could not find page with ID: missing-page-id.",
+            "request_id": "test-request-id"
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/pages"))
+        .respond_with(ResponseTemplate::new(502).set_body_string("Bad Gateway"))
+        .mount(&server)
+        .await;
+
+    let notion = Notion::builder()
+        .base_url(format!("{}/v1", server.uri()))
+        .retry_policy(RetryPolicy::none())
+        .build();
+    let error = notion
+        .retrieve_a_page(String::from("missing-page-id"))
+        .await
+        .unwrap_err();
+    match &error {
+        NotionError::Api {
+            status,
+            code: ErrorCode::ObjectNotFound,
+            request_id,
+            ..
+        } => {
+            assert_eq!(*status, 404);
+            assert_eq!(request_id.as_deref(), Some("test-request-id"));
+        }
+        _ => panic!("unexpected error: {:?}", error),
+    }
+    assert_eq!(error.request_id(), Some("test-request-id"));
+
+    let error = notion.create_a_page(&Page::default()).await.unwrap_err();
+    assert_eq!(error.status(), Some(502));
+    assert_eq!(error.code(), Some(&ErrorCode::BadGateway));
+
+    // a JSON body without `code` falls back to the HTTP status
+    let error = NotionError::from_response(504, r#"{"message": "upstream timed out"}"#, None);
+    assert_eq!(error.code(), Some(&ErrorCode::GatewayTimeout));
+    assert!(RetryPolicy::default().is_retryable(&error));
+}
+
+#[tokio::test]
+async fn test_retry() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/pages/test-page-id"))
+        .respond_with(
+            ResponseTemplate::new(429)
+                .insert_header("Retry-After", "0")
+                .set_body_json(json!({
+                    "object": "error",
+                    "status": 429,
+                    "code": "rate_limited",
+                    "message": "You have been rate limited."
+                })),
+        )
+        .up_to_n_times(2)
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/pages/test-page-id"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "object": "page",
+            "id": "test-page-id"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PATCH"))
+        .and(path("/v1/pages/test-page-id"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "object": "error",
+            "status": 400,
+            "code": "validation_error",
+            "message": "body failed validation."
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let notion = mock_notion(&server);
+    let page = notion
+        .retrieve_a_page(String::from("test-page-id"))
+        .await
+        .unwrap();
+    assert_eq!(page.id, "test-page-id");
+
+    // validation errors are not retried
+    let error = notion
+        .update_a_page(String::from("test-page-id"), &Page::default())
+        .await
+        .unwrap_err();
+    assert_eq!(error.code(), Some(&ErrorCode::ValidationError));
+}
+
+#[tokio::test]
+async fn test_retry_writes() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/pages"))
+        .respond_with(ResponseTemplate::new(502))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PATCH"))
+        .and(path("/v1/blocks/test-page-id/children"))
+        .respond_with(
+            ResponseTemplate::new(429)
+                .insert_header("Retry-After", "0")
+                .set_body_json(json!({
+                    "object": "error",
+                    "status": 429,
+                    "code": "rate_limited",
+                    "message": "You have been rate limited."
+                })),
+        )
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PATCH"))
+        .and(path("/v1/blocks/test-page-id/children"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "object": "list",
+            "results": []
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/databases/test-database-id/query"))
+        .respond_with(ResponseTemplate::new(502))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/databases/test-database-id/query"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "object": "list",
+            "results": [],
+            "has_more": false
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // a write is not repeated after an error it may have been applied despite
+    let notion = mock_notion(&server);
+    let error = notion.create_a_page(&Page::default()).await.unwrap_err();
+    assert_eq!(error.code(), Some(&ErrorCode::BadGateway));
+
+    // but it is after rate limiting
+    notion
+        .append_block_children(String::from("test-page-id"), vec![Block::default()])
+        .await
+        .unwrap();
+
+    // queries are read-only
+    notion
+        .query_database(String::from("test-database-id"), QueryFilter::new())
+        .await
+        .unwrap();
+
+    let policy = RetryPolicy::default();
+    let error = NotionError::from_response(502, "", None);
+    assert!(!policy.is_retryable_for(&error, false));
+    assert!(policy.is_retryable_for(&error, true));
+    let policy = RetryPolicy {
+        retry_non_idempotent: true,
+        ..policy
+    };
+    assert!(policy.is_retryable_for(&error, false));
+}
+
+#[test]
+fn test_retry_policy_delay() {
+    let policy = RetryPolicy {
+        base_delay: Duration::from_millis(100),
+        max_delay: Duration::from_millis(300),
+        jitter: 0.0,
+        ..Default::default()
+    };
+    let error = NotionError::from_response(503, "", None);
+    assert!(policy.is_retryable(&error));
+    assert_eq!(policy.delay(1, &error), Duration::from_millis(100));
+    assert_eq!(policy.delay(2, &error), Duration::from_millis(200));
+    assert_eq!(policy.delay(3, &error), Duration::from_millis(300));
+
+    let error = NotionError::from_response(429, "", Some(Duration::from_secs(7)));
+    assert_eq!(policy.delay(1, &error), Duration::from_millis(300));
+    assert_eq!(
+        RetryPolicy::default().delay(1, &error),
+        Duration::from_secs(7)
+    );
+    let error = NotionError::from_response(429, "", Some(Duration::from_secs(3600)));
+    assert_eq!(
+        RetryPolicy::default().delay(1, &error),
+        Duration::from_secs(30)
+    );
+
+    let policy = RetryPolicy {
+        jitter: 0.5,
+        ..policy
+    };
+    let delay = policy.delay(1, &NotionError::from_response(500, "", None));
+    assert!(delay >= Duration::from_millis(50) && delay <= Duration::from_millis(100));
+    assert!(!RetryPolicy::none().is_retryable(&NotionError::from_response(400, "", None)));
+}
+
+#[test]
+fn test_rate_limiter_reserve() {
+    let limiter = RateLimiter::with_burst(10.0, 2);
+    let now = std::time::Instant::now();
+    assert_eq!(limiter.reserve_at(now), Duration::ZERO);
+    assert_eq!(limiter.clone().reserve_at(now), Duration::ZERO);
+
+    assert_eq!(limiter.reserve_at(now), Duration::from_millis(100));
+    assert_eq!(limiter.clone().reserve_at(now), Duration::from_millis(200));
+
+    // tokens are refilled as time passes
+    let later = now + Duration::from_millis(250);
+    assert_eq!(limiter.reserve_at(later), Duration::from_millis(50));
+
+    // an earlier instant does not refill anything
+    assert_eq!(limiter.reserve_at(now), Duration::from_millis(150));
+
+    // a wait too long for a `Duration` saturates
+    let limiter = RateLimiter::new(1e-300);
+    assert_eq!(limiter.reserve_at(now), Duration::ZERO);
+    assert_eq!(limiter.reserve_at(now), Duration::MAX);
+    assert!(std::panic::catch_unwind(|| RateLimiter::new(f64::INFINITY)).is_err());
+    assert!(std::panic::catch_unwind(|| RateLimiter::new(f64::NAN)).is_err());
+}
+
+#[tokio::test]
+async fn test_rate_limiter_shared_across_tasks() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/pages/test-page-id"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "object": "page",
+            "id": "test-page-id"
+        })))
+        .expect(4)
+        .mount(&server)
+        .await;
+
+    let notion = Notion::builder()
+        .base_url(format!("{}/v1", server.uri()))
+        .rate_limiter(RateLimiter::new(20.0))
+        .build();
+
+    let start = std::time::Instant::now();
+    let handles = (0..4)
+        .map(|_| {
+            let notion = notion.clone();
+            tokio::spawn(async move {
+                notion
+                    .retrieve_a_page(String::from("test-page-id"))
+                    .await
+                    .unwrap()
+            })
+        })
+        .collect::<Vec<_>>();
+    for handle in handles {
+        handle.await.unwrap();
+    }
+    // the first request is free, the other three wait 50ms each
+    assert!(start.elapsed() >= Duration::from_millis(140));
+}
+
+#[tokio::test]
+async fn test_query_many_databases() {
+    let server = MockServer::start().await;
+    for database_id in ["database-a", "database-b"] {
+        Mock::given(method("POST"))
+            .and(path(format!("/v1/databases/{}/query", database_id)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "object": "list",
+                "results": [{"object": "page", "id": format!("{}-page", database_id)}],
+                "has_more": false
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let notion = std::sync::Arc::new(mock_notion(&server));
+    let handles = ["database-a", "database-b"]
+        .into_iter()
+        .map(|database_id| {
+            let notion = notion.clone();
+            tokio::spawn(async move {
+                notion
+                    .query_database(database_id.to_string(), QueryFilter::new())
+                    .await
+                    .unwrap()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut page_ids = Vec::new();
+    for handle in handles {
+        let response = handle.await.unwrap();
+        page_ids.push(response.results[0].id.clone());
+    }
+    assert_eq!(page_ids, vec!["database-a-page", "database-b-page"]);
+}
+
+#[tokio::test]
+async fn test_query_database_stream() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/databases/test-database-id/query"))
+        .and(body_partial_json(json!({"start_cursor": "cursor-2"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "object": "list",
+            "results": [{"object": "page", "id": "page-3"}],
+            "has_more": false,
+            "next_cursor": null
+        })))
+        .with_priority(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/databases/test-database-id/query"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "object": "list",
+            "results": [
+                {"object": "page", "id": "page-1"},
+                {"object": "page", "id": "page-2"}
+            ],
+            "has_more": true,
+            "next_cursor": "cursor-2"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let notion = mock_notion(&server);
+    let pages = notion
+        .query_database_all(String::from("test-database-id"), QueryFilter::new())
+        .await
+        .unwrap();
+    let page_ids = pages
+        .iter()
+        .map(|page| page.id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(page_ids, vec!["page-1", "page-2", "page-3"]);
+}
+
+#[tokio::test]
+async fn test_retrieve_block_children_stream() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/blocks/test-page-id/children"))
+        .and(query_param("start_cursor", "cursor-2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "object": "list",
+            "results": [{"object": "block", "id": "block-3", "type": "divider", "divider": {}}],
+            "has_more": false,
+            "next_cursor": null
+        })))
+        .with_priority(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/blocks/test-page-id/children"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "object": "list",
+            "results": [
+                {"object": "block", "id": "block-1", "type": "divider", "divider": {}},
+                {"object": "block", "id": "block-2", "type": "divider", "divider": {}}
+            ],
+            "has_more": true,
+            "next_cursor": "cursor-2"
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let notion = mock_notion(&server);
+    let response = notion
+        .retrieve_block_children(String::from("test-page-id"), None)
+        .await
+        .unwrap();
+    assert_eq!(response.has_more, Some(true));
+    assert_eq!(response.next_cursor.as_deref(), Some("cursor-2"));
+
+    let blocks = notion
+        .retrieve_block_children_all(String::from("test-page-id"))
+        .await
+        .unwrap();
+    let block_ids = blocks
+        .iter()
+        .map(|block| block.id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(block_ids, vec!["block-1", "block-2", "block-3"]);
+}
+
+#[tokio::test]
+async fn test_retrieve_page_content() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/blocks/test-page-id/children"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "object": "list",
+            "results": [
+                {"object": "block", "id": "block-1", "type": "toggle", "has_children": true, "toggle": {"rich_text": []}},
+                {"object": "block", "id": "block-2", "type": "paragraph", "paragraph": {"rich_text": []}},
+                {"object": "block", "id": "sub-page", "type": "child_page", "has_children": true, "child_page": {"title": "Sub page"}}
+            ],
+            "has_more": false
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/blocks/block-1/children"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "object": "list",
+            "results": [
+                {"object": "block", "id": "block-3", "type": "bulleted_list_item", "has_children": true, "bulleted_list_item": {"rich_text": []}}
+            ],
+            "has_more": false
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/blocks/block-3/children"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "object": "list",
+            "results": [
+                {"object": "block", "id": "block-4", "type": "paragraph", "paragraph": {"rich_text": []}}
+            ],
+            "has_more": false
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/blocks/sub-page/children"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let notion = mock_notion(&server);
+    let blocks = notion
+        .retrieve_page_content(String::from("test-page-id"))
+        .await
+        .unwrap();
+    assert_eq!(blocks.len(), 3);
+    assert_eq!(blocks[0].children.len(), 1);
+    assert_eq!(blocks[0].children[0].id, "block-3");
+    assert_eq!(blocks[0].children[0].children[0].id, "block-4");
+    assert!(blocks[1].children.is_empty());
+    assert!(blocks[2].children.is_empty());
+}
+
+#[tokio::test]
+async fn test_retrieve_update_delete_a_block() {
+    let server = MockServer::start().await;
+    let to_do = json!({
+        "object": "block",
+        "id": "block-1",
+        "type": "to_do",
+        "has_children": true,
+        "to_do": {"rich_text": [], "checked": false, "color": "default"}
+    });
+    Mock::given(method("GET"))
+        .and(path("/v1/blocks/block-1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(to_do.clone()))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PATCH"))
+        .and(path("/v1/blocks/block-1"))
+        .and(body_partial_json(json!({"to_do": {"checked": true}})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "object": "block",
+            "id": "block-1",
+            "type": "to_do",
+            "to_do": {"rich_text": [], "checked": true, "color": "default"}
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PATCH"))
+        .and(path("/v1/blocks/block-1"))
+        .and(body_json(json!({"archived": false})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(to_do.clone()))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/v1/blocks/block-1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "object": "block",
+            "id": "block-1",
+            "type": "to_do",
+            "archived": true,
+            "to_do": {"rich_text": [], "checked": true, "color": "default"}
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let notion = mock_notion(&server);
+    let mut block = notion
+        .retrieve_a_block(String::from("block-1"))
+        .await
+        .unwrap();
+    assert_eq!(block.id, "block-1");
+    assert!(!block.to_do.as_ref().unwrap().checked);
+
+    block.to_do.as_mut().unwrap().checked = true;
+    let block = notion
+        .update_a_block(String::from("block-1"), &block)
+        .await
+        .unwrap();
+    assert!(block.to_do.unwrap().checked);
+
+    let requests = server.received_requests().await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&requests[1].body).unwrap();
+    assert!(body.get("type").is_none());
+    assert!(body.get("object").is_none());
+    assert!(body["to_do"].get("children").is_none());
+
+    let block = notion
+        .delete_a_block(String::from("block-1"))
+        .await
+        .unwrap();
+    assert!(block.archived);
+
+    let block = notion
+        .restore_a_block(String::from("block-1"))
+        .await
+        .unwrap();
+    assert!(!block.archived);
+}
+
+#[tokio::test]
+async fn test_append_block_children_after() {
+    let server = MockServer::start().await;
+    let results = |ids: std::ops::Range<usize>| {
+        json!({
+            "object": "list",
+            "results": ids
+                .map(|i| json!({"object": "block", "id": format!("block-{}", i), "type": "divider", "divider": {}}))
+                .collect::<Vec<_>>()
+        })
+    };
+    Mock::given(method("PATCH"))
+        .and(path("/v1/blocks/test-page-id/children"))
+        .and(body_partial_json(json!({"after": "anchor"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(results(0..100)))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PATCH"))
+        .and(path("/v1/blocks/test-page-id/children"))
+        .and(body_partial_json(json!({"after": "block-99"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(results(100..150)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let notion = mock_notion(&server);
+    let blocks = (0..150)
+        .map(|_| Block::divider(ParentType::Page, String::from("test-page-id")))
+        .collect::<Vec<_>>();
+    let response = notion
+        .append_block_children_after(
+            String::from("test-page-id"),
+            blocks,
+            Some(String::from("anchor")),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.results.len(), 150);
+    assert_eq!(response.results[149].id, "block-149");
+}
+
+#[tokio::test]
+async fn test_append_block_children_deep() {
+    let server = MockServer::start().await;
+    let created = |ids: &[&str]| {
+        json!({
+            "object": "list",
+            "results": ids
+                .iter()
+                .map(|id| json!({"object": "block", "id": id, "type": "paragraph", "paragraph": {}}))
+                .collect::<Vec<_>>()
+        })
+    };
+    Mock::given(method("PATCH"))
+        .and(path("/v1/blocks/test-page-id/children"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(created(&["toggle-1", "paragraph-1"])),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/blocks/toggle-1/children"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(created(&["to-do-1"])))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PATCH"))
+        .and(path("/v1/blocks/to-do-1/children"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(created(&["paragraph-2"])))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PATCH"))
+        .and(path("/v1/blocks/paragraph-1/children"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(created(&["divider-1"])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let parent_id = String::from("test-page-id");
+    let mut level_3 =
+        Block::paragraph(ParentType::Page, parent_id.clone(), vec![String::from("3")]);
+    level_3.paragraph.as_mut().unwrap().children = vec![Block::paragraph(
+        ParentType::Page,
+        parent_id.clone(),
+        vec![String::from("4")],
+    )];
+    let mut to_do = Block::to_do(
+        ParentType::Page,
+        parent_id.clone(),
+        vec![String::from("2")],
+        false,
+    );
+    to_do.to_do.as_mut().unwrap().children = vec![level_3];
+    let mut toggle =
+        Block::toggle_blocks(ParentType::Page, parent_id.clone(), vec![String::from("1")]);
+    toggle.toggle.as_mut().unwrap().children = vec![to_do];
+    let mut paragraph =
+        Block::paragraph(ParentType::Page, parent_id.clone(), vec![String::from("1")]);
+    paragraph.children = vec![Block::divider(ParentType::Page, parent_id.clone())];
+
+    let notion = mock_notion(&server);
+    let response = notion
+        .append_block_children_deep(parent_id, vec![toggle, paragraph])
+        .await
+        .unwrap();
+    assert_eq!(response.results.len(), 2);
+
+    let requests = server.received_requests().await.unwrap();
+    let bodies = requests
+        .iter()
+        .filter(|request| request.method.as_str() == "PATCH")
+        .map(|request| serde_json::from_slice::<serde_json::Value>(&request.body).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(bodies.len(), 3);
+    let to_do = &bodies[0]["children"][0]["toggle"]["children"][0]["to_do"];
+    assert_eq!(to_do["children"], json!([]));
+    assert_eq!(bodies[0]["children"][1]["paragraph"]["children"], json!([]));
+    let level_3 = &bodies[1]["children"][0]["paragraph"];
+    assert_eq!(level_3["children"].as_array().unwrap().len(), 1);
+    assert_eq!(bodies[2]["children"][0]["type"], "divider");
+}
+
+#[tokio::test]
+async fn test_append_block_children_deep_request_size() {
+    let server = MockServer::start().await;
+    let created = |prefix: &str, count: usize| {
+        json!({
+            "object": "list",
+            "results": (0..count)
+                .map(|i| json!({"object": "block", "id": format!("{}-{}", prefix, i), "type": "toggle", "toggle": {}}))
+                .collect::<Vec<_>>()
+        })
+    };
+    Mock::given(method("PATCH"))
+        .and(path("/v1/blocks/test-page-id/children"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(created("toggle", 20)))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PATCH"))
+        .and(path_regex(r"^/v1/blocks/toggle-\d+/children$"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(created("item", 60)))
+        .mount(&server)
+        .await;
+
+    // 20 toggles with 60 children each: 1220 blocks
+    let parent_id = String::from("test-page-id");
+    let blocks = (0..20)
+        .map(|_| {
+            let mut toggle =
+                Block::toggle_blocks(ParentType::Page, parent_id.clone(), vec![String::from("T")]);
+            toggle.toggle.as_mut().unwrap().children = (0..60)
+                .map(|_| Block::divider(ParentType::Page, parent_id.clone()))
+                .collect();
+            toggle
+        })
+        .collect::<Vec<_>>();
+
+    let notion = mock_notion(&server);
+    let response = notion
+        .append_block_children_deep(parent_id, blocks)
+        .await
+        .unwrap();
+    assert_eq!(response.results.len(), 20);
+
+    fn count_blocks(body: &serde_json::Value) -> usize {
+        body["children"]
+            .as_array()
+            .map(|children| {
+                children
+                    .iter()
+                    .map(|child| 1 + count_blocks(&child[child["type"].as_str().unwrap()]))
+                    .sum()
+            })
+            .unwrap_or(0)
+    }
+    let sizes = server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .map(|request| count_blocks(&serde_json::from_slice(&request.body).unwrap()))
+        .collect::<Vec<_>>();
+    assert!(sizes.iter().all(|size| *size <= 1000), "{:?}", sizes);
+    assert_eq!(sizes.iter().sum::<usize>(), 1220);
+}
+
+#[tokio::test]
+async fn test_append_block_children_deep_table_and_columns() {
+    let server = MockServer::start().await;
+    let created = |ids: &[&str]| {
+        json!({
+            "object": "list",
+            "results": ids
+                .iter()
+                .map(|id| json!({"object": "block", "id": id, "type": "paragraph", "paragraph": {}}))
+                .collect::<Vec<_>>()
+        })
+    };
+    for (verb, block_id, ids) in [
+        ("PATCH", "test-page-id", vec!["toggle-1"]),
+        ("PATCH", "toggle-1", vec!["table-1", "column-list-1"]),
+        ("GET", "column-list-1", vec!["column-1", "column-2"]),
+        ("GET", "column-1", vec!["paragraph-1"]),
+        ("PATCH", "paragraph-1", vec!["paragraph-2"]),
+    ] {
+        Mock::given(method(verb))
+            .and(path(format!("/v1/blocks/{}/children", block_id)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(created(&ids)))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    // toggle > table > rows, and toggle > column list > columns > paragraph > paragraph
+    let parent_id = String::from("test-page-id");
+    let paragraph = |text: &str| {
+        Block::paragraph(
+            ParentType::Page,
+            parent_id.clone(),
+            vec![String::from(text)],
+        )
+    };
+    let mut table = Block::table(ParentType::Page, parent_id.clone());
+    let table_content = table.table.as_mut().unwrap();
+    table_content.table_width = 2;
+    table_content.children = vec![
+        Block::table_row(
+            ParentType::Page,
+            parent_id.clone(),
+            vec![String::from("a"), String::from("b")],
+        ),
+        Block::table_row(
+            ParentType::Page,
+            parent_id.clone(),
+            vec![String::from("c"), String::from("d")],
+        ),
+    ];
+    let mut nested = paragraph("left");
+    nested.paragraph.as_mut().unwrap().children = vec![paragraph("deep")];
+    let mut left = Block::column(ParentType::Page, parent_id.clone());
+    left.column.as_mut().unwrap().children = vec![nested];
+    let mut right = Block::column(ParentType::Page, parent_id.clone());
+    right.column.as_mut().unwrap().children = vec![paragraph("right")];
+    let mut column_list = Block::column_list(ParentType::Page, parent_id.clone());
+    column_list.column_list.as_mut().unwrap().children = vec![left, right];
+    let mut toggle =
+        Block::toggle_blocks(ParentType::Page, parent_id.clone(), vec![String::from("T")]);
+    toggle.toggle.as_mut().unwrap().children = vec![table, column_list];
+
+    let notion = mock_notion(&server);
+    let response = notion
+        .append_block_children_deep(parent_id, vec![toggle])
+        .await
+        .unwrap();
+    assert_eq!(response.results.len(), 1);
+
+    let bodies = server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .filter(|request| request.method.as_str() == "PATCH")
+        .map(|request| serde_json::from_slice::<serde_json::Value>(&request.body).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(bodies.len(), 3);
+    // a column list cannot be created two levels down, so the toggle is sent empty
+    assert_eq!(bodies[0]["children"][0]["toggle"]["children"], json!([]));
+    // tables and column lists are sent with the children they need
+    let table = &bodies[1]["children"][0]["table"];
+    assert_eq!(table["children"].as_array().unwrap().len(), 2);
+    assert_eq!(table["children"][1]["type"], "table_row");
+    let columns = bodies[1]["children"][1]["column_list"]["children"]
+        .as_array()
+        .unwrap();
+    assert_eq!(columns.len(), 2);
+    let left = &columns[0]["column"]["children"][0]["paragraph"];
+    assert_eq!(left["rich_text"][0]["plain_text"], "left");
+    assert_eq!(left["children"], json!([]));
+    assert_eq!(
+        columns[1]["column"]["children"].as_array().unwrap().len(),
+        1
+    );
+    // the paragraph three levels down is appended afterwards
+    assert_eq!(
+        bodies[2]["children"][0]["paragraph"]["rich_text"][0]["plain_text"],
+        "deep"
+    );
+}
+
+#[tokio::test]
+async fn test_query_database_page_size_and_filter_properties() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/databases/test-database-id/query"))
+        .and(query_param("filter_properties", "title"))
+        .and(query_param("filter_properties", ":UPp"))
+        .and(query_param("filter_properties", "a%b&c"))
+        .and(body_partial_json(json!({"page_size": 10})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "object": "list",
+            "results": [],
+            "has_more": false
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let notion = mock_notion(&server);
+    let mut filter = QueryFilter::new();
+    filter.page_size(10);
+    filter.filter_properties(vec![
+        String::from("title"),
+        String::from("%3AUPp"),
+        String::from("a%25b%26c"),
+    ]);
+    assert_eq!(filter.build(), r#"{"page_size":10}"#);
+
+    let response = notion
+        .query_database(String::from("test-database-id"), filter)
+        .await
+        .unwrap();
+    assert_eq!(response.results.len(), 0);
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(
+        requests[0].url.query(),
+        Some("filter_properties=title&filter_properties=%3AUPp&filter_properties=a%25b%26c")
+    );
+    assert_eq!(percent_decode("100%"), "100%");
+    assert_eq!(percent_decode("%zz%3a"), "%zz:");
+}
 
 #[tokio::test]
 async fn test_retrieve_database() {
-    let notion = Notion::new();
-    let response = notion.retrieve_a_database().await;
+    let notion = Notion::from_env().unwrap();
+    let database_id = std::env::var("NOTION_DATABASE_ID").unwrap();
+    let response = notion.retrieve_a_database(database_id).await;
 
     match response {
         Ok(database) => {
@@ -20,13 +1021,14 @@ async fn test_retrieve_database() {
 
 #[tokio::test]
 async fn test_query_database() {
-    let notion = Notion::new();
+    let notion = Notion::from_env().unwrap();
+    let database_id = std::env::var("NOTION_DATABASE_ID").unwrap();
     let mut filter = QueryFilter::new();
     filter.args(FilterItem::status(
         String::from("Status"),
         StatusFilterItem::equals(String::from("Deep Dive")),
     ));
-    let response = notion.query_database(filter).await;
+    let response = notion.query_database(database_id, filter).await;
 
     match response {
         Ok(response) => {
@@ -42,7 +1044,8 @@ async fn test_query_database() {
 
 #[tokio::test]
 async fn test_query_database_with_pagenation() {
-    let notion = Notion::new();
+    let notion = Notion::from_env().unwrap();
+    let database_id = std::env::var("NOTION_DATABASE_ID").unwrap();
     let mut filter = QueryFilter::new();
     filter.args(FilterItem::status(
         String::from("Status"),
@@ -53,7 +1056,9 @@ async fn test_query_database_with_pagenation() {
     let mut num_papers = 0;
 
     while has_more {
-        let response = notion.query_database(filter.clone()).await;
+        let response = notion
+            .query_database(database_id.clone(), filter.clone())
+            .await;
 
         match response {
             Ok(response) => {
@@ -74,7 +1079,8 @@ async fn test_query_database_with_pagenation() {
 
 #[tokio::test]
 async fn test_crud_a_page() {
-    let notion = Notion::new();
+    let notion = Notion::from_env().unwrap();
+    let database_id = std::env::var("NOTION_DATABASE_ID").unwrap();
     let mut page_id = "".to_string();
 
     // Create a page
@@ -98,7 +1104,7 @@ async fn test_crud_a_page() {
 
     let mut page = Page::from_properties(properties);
     page.parent.type_name = ParentType::Database;
-    page.parent.database_id = Some(notion.database_id.clone());
+    page.parent.database_id = Some(database_id.clone());
     let response = notion.create_a_page(&page).await;
 
     match response {
@@ -133,7 +1139,7 @@ async fn test_crud_a_page() {
     let mut page = Page::from_properties(properties);
     page.id = page_id.clone();
     page.parent.type_name = ParentType::Database;
-    page.parent.database_id = Some(notion.database_id.clone());
+    page.parent.database_id = Some(database_id.clone());
 
     let response = notion.update_a_page(page_id.clone(), &page).await;
 
@@ -150,11 +1156,7 @@ async fn test_crud_a_page() {
 
     // Delete a page
     let response = notion
-        .archive_a_page(
-            page_id.clone(),
-            notion.database_id.clone(),
-            ParentType::Database,
-        )
+        .archive_a_page(page_id.clone(), database_id.clone(), ParentType::Database)
         .await;
 
     match response {
@@ -171,7 +1173,8 @@ async fn test_crud_a_page() {
 
 #[tokio::test]
 async fn test_crud_blocks() {
-    let notion = Notion::new();
+    let notion = Notion::from_env().unwrap();
+    let database_id = std::env::var("NOTION_DATABASE_ID").unwrap();
     let mut page_id = "".to_string();
 
     // Create a page
@@ -190,7 +1193,7 @@ async fn test_crud_blocks() {
     );
     let mut page = Page::from_properties(properties);
     page.parent.type_name = ParentType::Database;
-    page.parent.database_id = Some(notion.database_id.clone());
+    page.parent.database_id = Some(database_id.clone());
     let response = notion.create_a_page(&page).await;
 
     match response {
@@ -292,11 +1295,7 @@ async fn test_crud_blocks() {
 
     // Delete a page
     let response = notion
-        .archive_a_page(
-            page_id.clone(),
-            notion.database_id.clone(),
-            ParentType::Database,
-        )
+        .archive_a_page(page_id.clone(), database_id.clone(), ParentType::Database)
         .await;
 
     match response {
@@ -313,7 +1312,8 @@ async fn test_crud_blocks() {
 
 #[tokio::test]
 async fn test_crud_blocks_many() {
-    let notion = Notion::new();
+    let notion = Notion::from_env().unwrap();
+    let database_id = std::env::var("NOTION_DATABASE_ID").unwrap();
     let mut page_id = "".to_string();
 
     // Create a page
@@ -332,7 +1332,7 @@ async fn test_crud_blocks_many() {
     );
     let mut page = Page::from_properties(properties);
     page.parent.type_name = ParentType::Database;
-    page.parent.database_id = Some(notion.database_id.clone());
+    page.parent.database_id = Some(database_id.clone());
     let response = notion.create_a_page(&page).await;
 
     match response {
@@ -415,11 +1415,7 @@ async fn test_crud_blocks_many() {
 
     // Delete a page
     let response = notion
-        .archive_a_page(
-            page_id.clone(),
-            notion.database_id.clone(),
-            ParentType::Database,
-        )
+        .archive_a_page(page_id.clone(), database_id.clone(), ParentType::Database)
         .await;
 
     match response {